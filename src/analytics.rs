@@ -0,0 +1,401 @@
+//! Analytics
+//! Cycle time metrics of incidents and tasks computed from their change history: the time
+//! spent in each status, the lead time from creation to closing, the number of reopenings
+//! and the mean time to resolve per priority or severity. All durations are in seconds.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    date::parse_date_time,
+    resources::{history::HistoryChangeSetDto, incident::IncidentDto, task::TaskDto},
+};
+
+/// The history field holding the status of an incident
+const INCIDENT_STATUS_FIELD: &str = "IncidentStatusId";
+
+/// The history field holding the status of a task
+const TASK_STATUS_FIELD: &str = "TaskStatusId";
+
+/// The status metrics of an artifact
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct StatusMetrics {
+    /// The seconds spent in each status, by status id. The current status counts up to
+    /// the date the metrics were computed on
+    pub time_in_status: BTreeMap<u64, i64>,
+
+    /// The seconds from the creation to the last closing, none while the artifact is open
+    pub lead_time: Option<i64>,
+
+    /// The number of times the artifact went from a closed status back to an open one
+    pub reopen_count: u64,
+}
+
+/// The status metrics of an incident
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct IncidentMetrics {
+    pub incident_id: Option<u64>,
+    pub priority_id: Option<u64>,
+    pub severity_id: Option<u64>,
+
+    #[serde(flatten)]
+    pub metrics: StatusMetrics,
+}
+
+/// The status metrics of a task
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TaskMetrics {
+    pub task_id: Option<u64>,
+    pub task_priority_id: Option<u64>,
+
+    #[serde(flatten)]
+    pub metrics: StatusMetrics,
+}
+
+/// The mean time to resolve the closed incidents sharing a priority or a severity
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct MeanTimeToResolve {
+    /// The id of the priority or severity, none for the incidents without one
+    pub id: Option<u64>,
+
+    /// The number of closed incidents
+    pub resolved_count: u64,
+
+    /// The mean lead time of the closed incidents
+    pub mean_time_to_resolve: i64,
+}
+
+/// Computes the status metrics of an incident from its change history.
+/// The closed statuses are the ones ending the incident lifecycle, e.g. Resolved and Closed
+pub fn incident_metrics(
+    incident: &IncidentDto,
+    history: &[HistoryChangeSetDto],
+    closed_status_ids: &[u64],
+    now: DateTime<Utc>,
+) -> IncidentMetrics {
+    let mut metrics = status_metrics(
+        incident.creation_date.as_deref(),
+        incident.incident_status_id,
+        INCIDENT_STATUS_FIELD,
+        history,
+        closed_status_ids,
+        now,
+    );
+
+    // incidents created directly in a closed status have no closing change in their history
+    if metrics.lead_time.is_none()
+        && is_closed(incident.incident_status_id, closed_status_ids)
+        && metrics.reopen_count == 0
+    {
+        metrics.lead_time = seconds_between(
+            incident.creation_date.as_deref(),
+            incident.closed_date.as_deref(),
+        );
+    }
+
+    IncidentMetrics {
+        incident_id: incident.incident_id,
+        priority_id: incident.priority_id,
+        severity_id: incident.severity_id,
+        metrics,
+    }
+}
+
+/// Computes the status metrics of a task from its change history.
+/// The closed statuses are the ones ending the task lifecycle, e.g. Completed and Obsolete
+pub fn task_metrics(
+    task: &TaskDto,
+    history: &[HistoryChangeSetDto],
+    closed_status_ids: &[u64],
+    now: DateTime<Utc>,
+) -> TaskMetrics {
+    TaskMetrics {
+        task_id: task.task_id,
+        task_priority_id: task.task_priority_id,
+        metrics: status_metrics(
+            task.creation_date.as_deref(),
+            task.task_status_id,
+            TASK_STATUS_FIELD,
+            history,
+            closed_status_ids,
+            now,
+        ),
+    }
+}
+
+/// The mean time to resolve the closed incidents, per priority
+pub fn mttr_by_priority(incidents: &[IncidentMetrics]) -> Vec<MeanTimeToResolve> {
+    mttr_by(incidents, |incident| incident.priority_id)
+}
+
+/// The mean time to resolve the closed incidents, per severity
+pub fn mttr_by_severity(incidents: &[IncidentMetrics]) -> Vec<MeanTimeToResolve> {
+    mttr_by(incidents, |incident| incident.severity_id)
+}
+
+fn mttr_by<F>(incidents: &[IncidentMetrics], key: F) -> Vec<MeanTimeToResolve>
+where
+    F: Fn(&IncidentMetrics) -> Option<u64>,
+{
+    let mut lead_times: BTreeMap<Option<u64>, Vec<i64>> = BTreeMap::new();
+    for incident in incidents {
+        if let Some(lead_time) = incident.metrics.lead_time {
+            lead_times.entry(key(incident)).or_default().push(lead_time);
+        }
+    }
+
+    lead_times
+        .into_iter()
+        .map(|(id, lead_times)| MeanTimeToResolve {
+            id,
+            resolved_count: lead_times.len() as u64,
+            mean_time_to_resolve: lead_times.iter().sum::<i64>() / lead_times.len() as i64,
+        })
+        .collect()
+}
+
+/// Replays the status changes of an artifact, oldest first, starting from its creation
+fn status_metrics(
+    creation_date: Option<&str>,
+    current_status_id: Option<u64>,
+    status_field: &str,
+    history: &[HistoryChangeSetDto],
+    closed_status_ids: &[u64],
+    now: DateTime<Utc>,
+) -> StatusMetrics {
+    let mut transitions: Vec<(DateTime<Utc>, Option<u64>, Option<u64>)> = history
+        .iter()
+        .filter_map(|change_set| {
+            let change = change_set.change_of(status_field)?;
+            let date = change_set.changed_on()?;
+
+            Some((
+                date,
+                change.old_value_int.map(|id| id as u64),
+                change.new_value_int.map(|id| id as u64),
+            ))
+        })
+        .collect();
+    transitions.sort_by_key(|(date, _, _)| *date);
+
+    let created = creation_date.and_then(parse_date_time);
+    let mut metrics = StatusMetrics::default();
+    let mut status_id = transitions
+        .first()
+        .map_or(current_status_id, |(_, old_status_id, _)| *old_status_id);
+    let mut since = created.or_else(|| transitions.first().map(|(date, _, _)| *date));
+    let mut closed_on = None;
+
+    for (date, old_status_id, new_status_id) in transitions {
+        add_time(&mut metrics, status_id, since, date);
+
+        match (
+            is_closed(old_status_id, closed_status_ids),
+            is_closed(new_status_id, closed_status_ids),
+        ) {
+            (false, true) => closed_on = Some(date),
+            (true, false) => {
+                metrics.reopen_count += 1;
+                closed_on = None;
+            }
+            _ => {}
+        }
+
+        status_id = new_status_id;
+        since = Some(date);
+    }
+    add_time(&mut metrics, status_id, since, now);
+
+    if let (Some(created), Some(closed_on)) = (created, closed_on) {
+        metrics.lead_time = Some((closed_on - created).num_seconds());
+    }
+
+    metrics
+}
+
+fn add_time(
+    metrics: &mut StatusMetrics,
+    status_id: Option<u64>,
+    since: Option<DateTime<Utc>>,
+    until: DateTime<Utc>,
+) {
+    if let (Some(status_id), Some(since)) = (status_id, since) {
+        *metrics.time_in_status.entry(status_id).or_default() +=
+            (until - since).num_seconds().max(0);
+    }
+}
+
+fn is_closed(status_id: Option<u64>, closed_status_ids: &[u64]) -> bool {
+    status_id.is_some_and(|status_id| closed_status_ids.contains(&status_id))
+}
+
+fn seconds_between(from: Option<&str>, to: Option<&str>) -> Option<i64> {
+    let from = parse_date_time(from?)?;
+    let to = parse_date_time(to?)?;
+
+    Some((to - from).num_seconds())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::resources::history::HistoryChangeDto;
+
+    const NEW: u64 = 1;
+    const OPEN: u64 = 2;
+    const CLOSED: u64 = 4;
+    const HOUR: i64 = 3600;
+
+    fn incident(status_id: u64, closed_date: Option<&str>) -> IncidentDto {
+        IncidentDto {
+            incident_id: Some(1),
+            incident_status_id: Some(status_id),
+            creation_date: Some("2022-03-14T00:00:00Z".to_string()),
+            closed_date: closed_date.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn change_set(hour: u32, field_name: &str, old: u64, new: u64) -> HistoryChangeSetDto {
+        HistoryChangeSetDto {
+            change_date: Some(format!("2022-03-14T{:02}:00:00.000", hour)),
+            details: Some(vec![HistoryChangeDto {
+                field_name: Some(field_name.to_string()),
+                old_value_int: Some(old as i64),
+                new_value_int: Some(new as i64),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }
+    }
+
+    fn status_change(hour: u32, old: u64, new: u64) -> HistoryChangeSetDto {
+        change_set(hour, INCIDENT_STATUS_FIELD, old, new)
+    }
+
+    fn at_hour(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2022, 3, 14, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn closed_reopened_and_closed_again() {
+        let history = vec![
+            status_change(1, NEW, CLOSED),
+            status_change(2, CLOSED, OPEN),
+            status_change(4, OPEN, CLOSED),
+        ];
+
+        let metrics = incident_metrics(
+            &incident(CLOSED, Some("2022-03-14T04:00:00Z")),
+            &history,
+            &[CLOSED],
+            at_hour(10),
+        )
+        .metrics;
+
+        assert_eq!(metrics.reopen_count, 1);
+        assert_eq!(metrics.lead_time, Some(4 * HOUR));
+        assert_eq!(
+            metrics.time_in_status,
+            BTreeMap::from([(NEW, HOUR), (OPEN, 2 * HOUR), (CLOSED, 7 * HOUR)])
+        );
+    }
+
+    #[test]
+    fn reopened_incident_has_no_lead_time() {
+        let history = vec![
+            status_change(1, NEW, CLOSED),
+            status_change(2, CLOSED, OPEN),
+        ];
+
+        let metrics =
+            incident_metrics(&incident(OPEN, None), &history, &[CLOSED], at_hour(10)).metrics;
+
+        assert_eq!(metrics.reopen_count, 1);
+        assert_eq!(metrics.lead_time, None);
+    }
+
+    #[test]
+    fn created_closed() {
+        let metrics = incident_metrics(
+            &incident(CLOSED, Some("2022-03-14T05:00:00Z")),
+            &[],
+            &[CLOSED],
+            at_hour(10),
+        )
+        .metrics;
+
+        assert_eq!(metrics.reopen_count, 0);
+        assert_eq!(metrics.lead_time, Some(5 * HOUR));
+        assert_eq!(
+            metrics.time_in_status,
+            BTreeMap::from([(CLOSED, 10 * HOUR)])
+        );
+    }
+
+    #[test]
+    fn history_out_of_order() {
+        let history = vec![
+            status_change(4, OPEN, CLOSED),
+            status_change(1, NEW, CLOSED),
+            status_change(2, CLOSED, OPEN),
+        ];
+        let mut ordered = history.clone();
+        ordered.sort_by_key(|change_set| change_set.change_date.clone());
+
+        let incident = incident(CLOSED, Some("2022-03-14T04:00:00Z"));
+        let metrics = incident_metrics(&incident, &history, &[CLOSED], at_hour(10)).metrics;
+        let expected = incident_metrics(&incident, &ordered, &[CLOSED], at_hour(10)).metrics;
+
+        assert_eq!(metrics.reopen_count, expected.reopen_count);
+        assert_eq!(metrics.lead_time, expected.lead_time);
+        assert_eq!(metrics.time_in_status, expected.time_in_status);
+        assert_eq!(metrics.lead_time, Some(4 * HOUR));
+    }
+
+    #[test]
+    fn no_status_changes() {
+        let task = TaskDto {
+            task_id: Some(1),
+            task_status_id: Some(OPEN),
+            creation_date: Some("2022-03-14T00:00:00Z".to_string()),
+            ..Default::default()
+        };
+        let history = vec![change_set(3, "TaskPriorityId", 1, 2)];
+
+        let metrics = task_metrics(&task, &history, &[CLOSED], at_hour(10)).metrics;
+
+        assert_eq!(metrics.reopen_count, 0);
+        assert_eq!(metrics.lead_time, None);
+        assert_eq!(metrics.time_in_status, BTreeMap::from([(OPEN, 10 * HOUR)]));
+    }
+
+    #[test]
+    fn mttr_groups_the_closed_incidents() {
+        let resolved = |priority_id, lead_time| IncidentMetrics {
+            priority_id,
+            metrics: StatusMetrics {
+                lead_time,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let incidents = vec![
+            resolved(Some(1), Some(100)),
+            resolved(Some(2), None),
+            resolved(None, Some(50)),
+            resolved(Some(1), Some(300)),
+        ];
+
+        let mttr: Vec<_> = mttr_by_priority(&incidents)
+            .into_iter()
+            .map(|mttr| (mttr.id, mttr.resolved_count, mttr.mean_time_to_resolve))
+            .collect();
+
+        assert_eq!(mttr, vec![(None, 1, 50), (Some(1), 2, 200)]);
+    }
+}
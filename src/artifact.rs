@@ -0,0 +1,78 @@
+//! Artifact types
+//! The kinds of artifacts managed by Spira, used by the apis shared between artifacts
+//! like custom properties, permissions and history.
+
+/// A Spira artifact type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArtifactType {
+    Requirement,
+    TestCase,
+    Incident,
+    Release,
+    TestRun,
+    Task,
+    TestStep,
+    TestSet,
+    AutomationHost,
+    RequirementStep,
+    Document,
+    Risk,
+}
+
+impl ArtifactType {
+    /// The id Spira uses for the artifact type
+    pub fn id(&self) -> u64 {
+        match self {
+            ArtifactType::Requirement => 1,
+            ArtifactType::TestCase => 2,
+            ArtifactType::Incident => 3,
+            ArtifactType::Release => 4,
+            ArtifactType::TestRun => 5,
+            ArtifactType::Task => 6,
+            ArtifactType::TestStep => 7,
+            ArtifactType::TestSet => 8,
+            ArtifactType::AutomationHost => 9,
+            ArtifactType::RequirementStep => 12,
+            ArtifactType::Document => 13,
+            ArtifactType::Risk => 14,
+        }
+    }
+
+    /// The name Spira uses for the artifact type in urls, e.g. "TestCase"
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArtifactType::Requirement => "Requirement",
+            ArtifactType::TestCase => "TestCase",
+            ArtifactType::Incident => "Incident",
+            ArtifactType::Release => "Release",
+            ArtifactType::TestRun => "TestRun",
+            ArtifactType::Task => "Task",
+            ArtifactType::TestStep => "TestStep",
+            ArtifactType::TestSet => "TestSet",
+            ArtifactType::AutomationHost => "AutomationHost",
+            ArtifactType::RequirementStep => "RequirementStep",
+            ArtifactType::Document => "Document",
+            ArtifactType::Risk => "Risk",
+        }
+    }
+
+    pub fn from_id(id: u64) -> Option<Self> {
+        let artifact_type = match id {
+            1 => ArtifactType::Requirement,
+            2 => ArtifactType::TestCase,
+            3 => ArtifactType::Incident,
+            4 => ArtifactType::Release,
+            5 => ArtifactType::TestRun,
+            6 => ArtifactType::Task,
+            7 => ArtifactType::TestStep,
+            8 => ArtifactType::TestSet,
+            9 => ArtifactType::AutomationHost,
+            12 => ArtifactType::RequirementStep,
+            13 => ArtifactType::Document,
+            14 => ArtifactType::Risk,
+            _ => return None,
+        };
+
+        Some(artifact_type)
+    }
+}
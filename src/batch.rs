@@ -0,0 +1,163 @@
+//! Batch operations
+//! Runs the create, update and delete operations of many artifacts with a bounded number
+//! of requests in flight. Every item gets its own result, index-aligned with the input,
+//! so a failing item doesn't stop the rest of the batch.
+
+use std::future::Future;
+
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+
+use crate::{error::InvalidPayloadError, Response};
+
+/// The number of requests in flight by default
+const DEFAULT_CONCURRENCY: usize = 8;
+
+/// The result of each item of a batch: the id of the artifact, none for the
+/// creations of a dry run
+pub type BatchResults = Vec<Response<Option<u64>>>;
+
+/// How a batch is run
+#[derive(Debug, Clone, Copy)]
+pub struct BatchOptions {
+    /// The maximum number of requests in flight
+    pub concurrency: usize,
+
+    /// Only validate the payloads, without sending them
+    pub dry_run: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        BatchOptions {
+            concurrency: DEFAULT_CONCURRENCY,
+            dry_run: false,
+        }
+    }
+}
+
+impl BatchOptions {
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+/// Runs the operation on every item, keeping the results in the order of the items
+pub(crate) async fn run<T, F, Fut>(
+    items: Vec<T>,
+    options: &BatchOptions,
+    operation: F,
+) -> BatchResults
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = Response<Option<u64>>>,
+{
+    stream::iter(items.into_iter().map(operation))
+        .buffered(options.concurrency.max(1))
+        .collect()
+        .await
+}
+
+/// Checks a payload before sending it: its required fields must be set and it must serialize
+pub(crate) fn validate<T: Serialize>(item: &T, missing_fields: Vec<&'static str>) -> Response<()> {
+    if !missing_fields.is_empty() {
+        return Err(Box::new(InvalidPayloadError { missing_fields }));
+    }
+    serde_json::to_string(item)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        resources::incident::IncidentDto,
+        transport::{
+            recording::{RecordingTransport, Reply},
+            HttpRequest, Method, StatusCode,
+        },
+    };
+
+    fn incidents(names: &[&str]) -> Vec<IncidentDto> {
+        names
+            .iter()
+            .map(|name| IncidentDto {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Creates the incidents with the id 100 + their name, the first ones completing last
+    fn create(request: &HttpRequest) -> Reply {
+        let mut incident: IncidentDto =
+            serde_json::from_str(request.body.as_deref().unwrap()).unwrap();
+        let number: u64 = incident.name.parse().unwrap();
+        incident.incident_id = Some(100 + number);
+
+        Reply::json(&incident).after_yields(10 - number as usize)
+    }
+
+    fn ids(results: &BatchResults) -> Vec<Option<Option<u64>>> {
+        results
+            .iter()
+            .map(|result| result.as_ref().ok().copied())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn results_keep_the_order_of_the_items() {
+        let transport = RecordingTransport::new(create);
+
+        let results = transport
+            .client()
+            .incident
+            .create_batch(1, incidents(&["0", "1", "", "3"]), &BatchOptions::default())
+            .await;
+
+        assert_eq!(
+            ids(&results),
+            vec![Some(Some(100)), Some(Some(101)), None, Some(Some(103))]
+        );
+        assert!(results[2].as_ref().unwrap_err().is::<InvalidPayloadError>());
+        assert_eq!(transport.requests_with(Method::Post).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn dry_runs_send_nothing() {
+        let transport = RecordingTransport::new(create);
+        let options = BatchOptions::default().with_dry_run(true);
+        let client = transport.client();
+
+        let created = client
+            .incident
+            .create_batch(1, incidents(&["0", ""]), &options)
+            .await;
+        let deleted = client.incident.delete_batch(1, vec![1, 2], &options).await;
+
+        assert_eq!(ids(&created), vec![Some(None), None]);
+        assert_eq!(ids(&deleted), vec![Some(Some(1)), Some(Some(2))]);
+        assert!(transport.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_deletes_are_reported() {
+        let transport = RecordingTransport::new(|_| Reply::status(StatusCode::NOT_FOUND));
+
+        let results = transport
+            .client()
+            .incident
+            .delete_batch(1, vec![1, 2], &BatchOptions::default())
+            .await;
+
+        assert_eq!(ids(&results), vec![None, None]);
+        assert_eq!(transport.requests_with(Method::Delete).len(), 2);
+    }
+}
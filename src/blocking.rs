@@ -0,0 +1,482 @@
+//! Blocking client
+//! The same resource clients as the async SpiraClient, with synchronous methods for the
+//! tools that don't run an async runtime. Enabled by the `blocking` cargo feature.
+//!
+//! Each method runs the async method of the same name to completion on a runtime owned by
+//! the client, so the blocking client must not be used from within an async runtime.
+//!
+//! ## Usage Example
+//! ```rust,no_run
+//! use spira::blocking::SpiraClient;
+//! use std::env;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let api_key = env::var("SPIRA_API_KEY")?;
+//!     let username = env::var("SPIRA_USERNAME")?;
+//!     let base_url = env::var("SPIRA_API_URL")?;
+//!
+//!     let spira_client = SpiraClient::new(&base_url, &api_key, &username)?;
+//!     let projects = spira_client.project.list()?;
+//!
+//!     println!("{:#?}", projects);
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use tokio::runtime::{self, Runtime};
+
+use crate::{
+    artifact::ArtifactType,
+    batch::{BatchOptions, BatchResults},
+    resources::{
+        custom_property::{CustomListDto, CustomListValueDto, CustomPropertyDto},
+        incident::{self, IncidentChange, IncidentDto},
+        project::{self, ProjectDto},
+        project_template::{
+            self, IncidentPriorityDto, IncidentSeverityDto, IncidentStatusDto, IncidentTypeDto,
+            ProjectTemplateDto, RequirementImportanceDto, RequirementStatusDto, RequirementTypeDto,
+            RiskImpactDto, RiskProbabilityDto, RiskStatusDto, RiskTypeDto, TaskPriorityDto,
+            TaskStatusDto, TaskTypeDto,
+        },
+        release::{self, ReleaseDto, ReleaseTestCaseDto, ReleaseTree},
+        requirement::{
+            self, RequirementChange, RequirementDto, RequirementStepDto, RequirementTree,
+        },
+        task::{self, TaskChange, TaskDto, TaskFolderDto, TaskFolderTree},
+        user::{self, UserDto},
+    },
+    transport::Transport,
+    Response,
+};
+
+/// Declares a blocking client wrapping an async one, each method running the async method
+/// of the same name on the runtime shared by the clients
+macro_rules! blocking_client {
+    (
+        $(#[$client_doc:meta])*
+        $name:ident($inner:ty) {
+            $(
+                $(#[$doc:meta])*
+                fn $method:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;
+            )*
+        }
+    ) => {
+        $(#[$client_doc])*
+        pub struct $name<'a> {
+            inner: $inner,
+            runtime: Arc<Runtime>,
+        }
+
+        impl<'a> $name<'a> {
+            $(
+                $(#[$doc])*
+                pub fn $method(&self $(, $arg: $ty)*) -> $ret {
+                    self.runtime.block_on(self.inner.$method($($arg),*))
+                }
+            )*
+        }
+    };
+}
+
+/// The blocking client, contains the synchronous child clients
+pub struct SpiraClient<'a> {
+    pub task: TaskClient<'a>,
+    pub project: ProjectClient<'a>,
+    pub user: UserClient<'a>,
+    pub requirement: RequirementClient<'a>,
+    pub incident: IncidentClient<'a>,
+    pub release: ReleaseClient<'a>,
+    pub project_template: ProjectTemplateClient<'a>,
+}
+
+impl<'a> SpiraClient<'a> {
+    pub fn new(base_url: &'a str, api_key: &str, username: &str) -> Response<Self> {
+        Self::from_async(crate::SpiraClient::new(base_url, api_key, username)?)
+    }
+
+    /// Builds the client on top of another transport than reqwest, see the transport module
+    pub fn with_transport(
+        base_url: &'a str,
+        api_key: &str,
+        username: &str,
+        transport: Arc<dyn Transport>,
+    ) -> Response<Self> {
+        Self::from_async(crate::SpiraClient::with_transport(
+            base_url, api_key, username, transport,
+        )?)
+    }
+
+    fn from_async(spira: crate::SpiraClient<'a>) -> Response<Self> {
+        let runtime = Arc::new(
+            runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+        );
+
+        Ok(SpiraClient {
+            task: TaskClient {
+                inner: spira.task,
+                runtime: runtime.clone(),
+            },
+            project: ProjectClient {
+                inner: spira.project,
+                runtime: runtime.clone(),
+            },
+            user: UserClient {
+                inner: spira.user,
+                runtime: runtime.clone(),
+            },
+            requirement: RequirementClient {
+                inner: spira.requirement,
+                runtime: runtime.clone(),
+            },
+            incident: IncidentClient {
+                inner: spira.incident,
+                runtime: runtime.clone(),
+            },
+            release: ReleaseClient {
+                inner: spira.release,
+                runtime: runtime.clone(),
+            },
+            project_template: ProjectTemplateClient {
+                inner: spira.project_template,
+                runtime,
+            },
+        })
+    }
+}
+
+blocking_client! {
+    /// The blocking Task Artifact Submodule
+    TaskClient(task::TaskClient<'a>) {
+        /// Retrieves all tasks owned by the currently authenticated user
+        fn list_my(&self) -> Response<Vec<TaskDto>>;
+        /// Retrieves a single task in the system
+        fn get(&self, project_id: u64, task_id: u64) -> Response<TaskDto>;
+        /// Creates a new task in the system
+        fn create(&self, project_id: u64, task: TaskDto) -> Response<TaskDto>;
+        /// Updates a task in the system. When the task was modified since it was retrieved,
+        /// fails with a ConflictError holding the current copy of the task
+        fn update(&self, project_id: u64, task: TaskDto) -> Response<()>;
+        /// Applies the changes to the latest version of the task on the server, leaving
+        /// its other fields untouched. Concurrency conflicts are retried on the fresh copy
+        fn patch(&self, project_id: u64, task_id: u64, changes: &[TaskChange]) -> Response<()>;
+        /// Deletes a task in the system
+        fn delete(&self, project_id: u64, task_id: u64) -> Response<()>;
+        /// Retrieves all the task folders of the project
+        fn folder_list(&self, project_id: u64) -> Response<Vec<TaskFolderDto>>;
+        /// Retrieves all the task folders of the project as a tree
+        fn folder_tree(&self, project_id: u64) -> Response<TaskFolderTree>;
+        /// Retrieves a single task folder
+        fn folder_get(&self, project_id: u64, task_folder_id: u64) -> Response<TaskFolderDto>;
+        /// Creates a new task folder, under the parent folder when one is set
+        fn folder_create(&self, project_id: u64, folder: TaskFolderDto) -> Response<TaskFolderDto>;
+        /// Updates a task folder
+        fn folder_update(&self, project_id: u64, folder: TaskFolderDto) -> Response<()>;
+        /// Renames a task folder
+        fn folder_rename(&self, project_id: u64, task_folder_id: u64, name: &str) -> Response<()>;
+        /// Moves a task folder with its content under another folder,
+        /// or to the top level when no parent folder is provided
+        fn folder_move(&self, project_id: u64, task_folder_id: u64, parent_task_folder_id: Option<u64>) -> Response<()>;
+        /// Deletes a task folder, its tasks are moved to the top level
+        fn folder_delete(&self, project_id: u64, task_folder_id: u64) -> Response<()>;
+        /// Retrieves a page of the tasks in a task folder.
+        /// Note: the starting row is 1 based
+        fn folder_task_list(&self, project_id: u64, task_folder_id: u64, starting_row: u64, number_of_rows: u64) -> Response<Vec<TaskDto>>;
+        /// Creates the tasks of the batch, the results holding the ids assigned by the server
+        fn create_batch(&self, project_id: u64, tasks: Vec<TaskDto>, options: &BatchOptions) -> BatchResults;
+        /// Updates the tasks of the batch
+        fn update_batch(&self, project_id: u64, tasks: Vec<TaskDto>, options: &BatchOptions) -> BatchResults;
+        /// Deletes the tasks of the batch
+        fn delete_batch(&self, project_id: u64, task_ids: Vec<u64>, options: &BatchOptions) -> BatchResults;
+    }
+}
+
+blocking_client! {
+    /// The blocking Incident Artifact Submodule
+    IncidentClient(incident::IncidentClient<'a>) {
+        /// Retrieves all incidents owned by the currently authenticated user
+        fn list_my(&self) -> Response<Vec<IncidentDto>>;
+        /// Retrieves a single incident in the system
+        fn get(&self, project_id: u64, incident_id: u64) -> Response<IncidentDto>;
+        /// Creates a new incident in the specified project in the system
+        fn create(&self, project_id: u64, incident: IncidentDto) -> Response<IncidentDto>;
+        /// Updates an incident in the system. When the incident was modified since it was retrieved,
+        /// fails with a ConflictError holding the current copy of the incident
+        fn update(&self, project_id: u64, incident: IncidentDto) -> Response<()>;
+        /// Applies the changes to the latest version of the incident on the server, leaving
+        /// its other fields untouched. Concurrency conflicts are retried on the fresh copy
+        fn patch(&self, project_id: u64, incident_id: u64, changes: &[IncidentChange]) -> Response<()>;
+        /// Deletes an incident in the system
+        fn delete(&self, project_id: u64, incident_id: u64) -> Response<()>;
+        /// Creates the incidents of the batch, the results holding the ids assigned by the server
+        fn create_batch(&self, project_id: u64, incidents: Vec<IncidentDto>, options: &BatchOptions) -> BatchResults;
+        /// Updates the incidents of the batch
+        fn update_batch(&self, project_id: u64, incidents: Vec<IncidentDto>, options: &BatchOptions) -> BatchResults;
+        /// Deletes the incidents of the batch
+        fn delete_batch(&self, project_id: u64, incident_ids: Vec<u64>, options: &BatchOptions) -> BatchResults;
+    }
+}
+
+blocking_client! {
+    /// The blocking Requirement Artifact Submodule
+    RequirementClient(requirement::RequirementClient<'a>) {
+        /// Retrieves all requirements owned by the currently authenticated user
+        fn list_my(&self) -> Response<Vec<RequirementDto>>;
+        /// Retrieves a page of the requirements in the project, in hierarchy order.
+        /// Note: the starting row is 1 based
+        fn list(&self, project_id: u64, starting_row: u64, number_of_rows: u64) -> Response<Vec<RequirementDto>>;
+        /// Retrieves all the requirements of the project arranged in their hierarchy
+        fn tree(&self, project_id: u64) -> Response<RequirementTree>;
+        /// Retrieves the direct children of a requirement
+        fn children(&self, project_id: u64, requirement_id: u64) -> Response<Vec<RequirementDto>>;
+        /// Retrieves a single requirement in the system
+        fn get(&self, project_id: u64, requirement_id: u64) -> Response<RequirementDto>;
+        /// Creates a new requirement record in the current project at the end of the list the user has access to.
+        /// Note: the indent level is set at the same as the last one in the list the user has access to
+        fn create(&self, project_id: u64, requirement: RequirementDto) -> Response<RequirementDto>;
+        /// Creates a new requirement as the last child of the specified parent requirement
+        fn create_child(&self, project_id: u64, parent_requirement_id: u64, requirement: RequirementDto) -> Response<RequirementDto>;
+        /// Indents a requirement, making it a child of the requirement above it
+        fn indent(&self, project_id: u64, requirement_id: u64) -> Response<()>;
+        /// Outdents a requirement, making it a sibling of its current parent
+        fn outdent(&self, project_id: u64, requirement_id: u64) -> Response<()>;
+        /// Moves a requirement, with its children, just before the destination requirement.
+        /// When no destination is provided the requirement is moved to the end of the list
+        fn move_to(&self, project_id: u64, requirement_id: u64, destination_requirement_id: Option<u64>) -> Response<()>;
+        /// Moves a requirement, with its children, to become the first child of the parent requirement
+        fn move_under(&self, project_id: u64, requirement_id: u64, parent_requirement_id: u64) -> Response<()>;
+        /// Updates a requirement in the system. When the requirement was modified since it was retrieved,
+        /// fails with a ConflictError holding the current copy of the requirement
+        fn update(&self, project_id: u64, requirement: RequirementDto) -> Response<()>;
+        /// Applies the changes to the latest version of the requirement on the server, leaving
+        /// its other fields untouched. Concurrency conflicts are retried on the fresh copy
+        fn patch(&self, project_id: u64, requirement_id: u64, changes: &[RequirementChange]) -> Response<()>;
+        /// Deletes a requirement in the system
+        fn delete(&self, project_id: u64, requirement_id: u64) -> Response<()>;
+        /// Retrieves the scenario steps of a Use Case requirement, ordered by position
+        fn step_list(&self, project_id: u64, requirement_id: u64) -> Response<Vec<RequirementStepDto>>;
+        /// Retrieves a single scenario step of a requirement
+        fn step_get(&self, project_id: u64, requirement_id: u64, requirement_step_id: u64) -> Response<RequirementStepDto>;
+        /// Adds a scenario step to a requirement, inserted before the existing step when one
+        /// is provided, otherwise at the end of the scenario
+        fn step_create(&self, project_id: u64, requirement_id: u64, step: RequirementStepDto, existing_requirement_step_id: Option<u64>) -> Response<RequirementStepDto>;
+        /// Updates a scenario step of a requirement
+        fn step_update(&self, project_id: u64, requirement_id: u64, step: RequirementStepDto) -> Response<()>;
+        /// Moves a scenario step just before the destination step.
+        /// When no destination is provided the step is moved to the end of the scenario
+        fn step_move(&self, project_id: u64, requirement_id: u64, requirement_step_id: u64, destination_requirement_step_id: Option<u64>) -> Response<()>;
+        /// Deletes a scenario step of a requirement
+        fn step_delete(&self, project_id: u64, requirement_id: u64, requirement_step_id: u64) -> Response<()>;
+        /// Creates the requirements of the batch, the results holding the ids assigned by the server
+        fn create_batch(&self, project_id: u64, requirements: Vec<RequirementDto>, options: &BatchOptions) -> BatchResults;
+        /// Updates the requirements of the batch
+        fn update_batch(&self, project_id: u64, requirements: Vec<RequirementDto>, options: &BatchOptions) -> BatchResults;
+        /// Deletes the requirements of the batch
+        fn delete_batch(&self, project_id: u64, requirement_ids: Vec<u64>, options: &BatchOptions) -> BatchResults;
+    }
+}
+
+blocking_client! {
+    /// The blocking Release Artifact Submodule
+    ReleaseClient(release::ReleaseClient<'a>) {
+        /// Retrieves all the releases belonging to the current project
+        fn list(&self, project_id: u64) -> Response<Vec<ReleaseDto>>;
+        /// Retrieves all the releases of the project arranged in their hierarchy
+        fn tree(&self, project_id: u64) -> Response<ReleaseTree>;
+        /// Retrieves a single release in the system
+        fn get(&self, project_id: u64, release_id: u64) -> Response<ReleaseDto>;
+        /// Creates a new release at the end of the project's release list
+        fn create(&self, project_id: u64, release: ReleaseDto) -> Response<ReleaseDto>;
+        /// Creates a new release or iteration as a child of the specified parent release
+        fn create_child(&self, project_id: u64, parent_release_id: u64, release: ReleaseDto) -> Response<ReleaseDto>;
+        /// Updates a release in the system. When the release was modified since it was retrieved,
+        /// fails with a ConflictError holding the current copy of the release
+        fn update(&self, project_id: u64, release: ReleaseDto) -> Response<()>;
+        /// Deletes a release in the system
+        fn delete(&self, project_id: u64, release_id: u64) -> Response<()>;
+        /// Retrieves the test cases mapped to a release
+        fn test_case_list(&self, project_id: u64, release_id: u64) -> Response<Vec<ReleaseTestCaseDto>>;
+        /// Maps a list of test cases to a release
+        fn test_case_add(&self, project_id: u64, release_id: u64, test_case_ids: &[u64]) -> Response<()>;
+        /// Removes the mapping between a test case and a release
+        fn test_case_remove(&self, project_id: u64, release_id: u64, test_case_id: u64) -> Response<()>;
+    }
+}
+
+blocking_client! {
+    /// The blocking Project Submodule
+    ProjectClient(project::ProjectClient<'a>) {
+        fn list(&self) -> Response<Vec<ProjectDto>>;
+        /// Retrieves a single project
+        fn get(&self, project_id: u64) -> Response<ProjectDto>;
+        /// Creates a new project. When an existing project is provided, its configuration and
+        /// artifacts are copied into the new project, otherwise the project uses the template
+        /// set on `project_template_id` or the default one
+        fn create(&self, project: ProjectDto, existing_project_id: Option<u64>) -> Response<ProjectDto>;
+        /// Updates a project
+        fn update(&self, project: ProjectDto) -> Response<()>;
+        /// Deletes a project with all its artifacts
+        fn delete(&self, project_id: u64) -> Response<()>;
+        /// Recalculates the task progress and test execution status of the project releases,
+        /// or of a single release when one is provided
+        fn refresh_progress(&self, project_id: u64, release_id: Option<u64>) -> Response<()>;
+    }
+}
+
+blocking_client! {
+    /// The blocking User Submodule
+    UserClient(user::UserClient<'a>) {
+        /// Retrieves the members of a project, with their project role
+        fn list(&self, project_id: u64) -> Response<Vec<UserDto>>;
+        /// Retrieves the currently authenticated user
+        fn current(&self) -> Response<UserDto>;
+        /// Retrieves a single user by id
+        fn get(&self, user_id: u64) -> Response<UserDto>;
+        /// Retrieves a single user by login
+        fn get_by_user_name(&self, user_name: &str) -> Response<UserDto>;
+        /// Creates a new user, the password is ignored for users authenticated against LDAP
+        fn create(&self, user: UserDto, password: &str, password_question: &str, password_answer: &str) -> Response<UserDto>;
+        /// Updates a user
+        fn update(&self, user: UserDto) -> Response<()>;
+        /// Deactivates a user, Spira keeps the user on the artifacts it created or owns
+        fn deactivate(&self, user_id: u64) -> Response<()>;
+        /// Adds a user to a project with the given project role
+        fn member_add(&self, project_id: u64, user_id: u64, project_role_id: u64) -> Response<()>;
+        /// Changes the project role of a member of a project
+        fn member_update(&self, project_id: u64, user_id: u64, project_role_id: u64) -> Response<()>;
+        /// Removes a user from a project
+        fn member_remove(&self, project_id: u64, user_id: u64) -> Response<()>;
+    }
+}
+
+blocking_client! {
+    /// The blocking Project Template Submodule
+    ProjectTemplateClient(project_template::ProjectTemplateClient<'a>) {
+        /// Retrieves all the project templates the current user has access to
+        fn list(&self) -> Response<Vec<ProjectTemplateDto>>;
+        /// Retrieves a single project template
+        fn get(&self, project_template_id: u64) -> Response<ProjectTemplateDto>;
+        fn incident_status_list(&self, project_template_id: u64) -> Response<Vec<IncidentStatusDto>>;
+        fn incident_priority_list(&self, project_template_id: u64) -> Response<Vec<IncidentPriorityDto>>;
+        fn incident_severity_list(&self, project_template_id: u64) -> Response<Vec<IncidentSeverityDto>>;
+        fn incident_type_list(&self, project_template_id: u64) -> Response<Vec<IncidentTypeDto>>;
+        fn task_status_list(&self, project_template_id: u64) -> Response<Vec<TaskStatusDto>>;
+        fn task_type_list(&self, project_template_id: u64) -> Response<Vec<TaskTypeDto>>;
+        fn task_priority_list(&self, project_template_id: u64) -> Response<Vec<TaskPriorityDto>>;
+        fn requirement_status_list(&self, project_template_id: u64) -> Response<Vec<RequirementStatusDto>>;
+        fn requirement_type_list(&self, project_template_id: u64) -> Response<Vec<RequirementTypeDto>>;
+        fn requirement_importance_list(&self, project_template_id: u64) -> Response<Vec<RequirementImportanceDto>>;
+        fn risk_status_list(&self, project_template_id: u64) -> Response<Vec<RiskStatusDto>>;
+        fn risk_type_list(&self, project_template_id: u64) -> Response<Vec<RiskTypeDto>>;
+        fn risk_probability_list(&self, project_template_id: u64) -> Response<Vec<RiskProbabilityDto>>;
+        fn risk_impact_list(&self, project_template_id: u64) -> Response<Vec<RiskImpactDto>>;
+        /// Retrieves the custom property definitions of an artifact type
+        fn custom_property_list(&self, project_template_id: u64, artifact_type: ArtifactType) -> Response<Vec<CustomPropertyDto>>;
+        /// Retrieves the custom lists of a project template
+        fn custom_list_list(&self, project_template_id: u64) -> Response<Vec<CustomListDto>>;
+        /// Retrieves a single custom list with its values
+        fn custom_list_get(&self, project_template_id: u64, custom_list_id: u64) -> Response<CustomListDto>;
+        /// Creates a new custom list, with its values, in a project template
+        fn custom_list_create(&self, project_template_id: u64, custom_list: CustomListDto) -> Response<CustomListDto>;
+        /// Updates a custom list, including the name and active flag of its values
+        fn custom_list_update(&self, project_template_id: u64, custom_list: CustomListDto) -> Response<()>;
+        /// Deactivates a custom list, Spira does not allow deleting them
+        fn custom_list_deactivate(&self, project_template_id: u64, custom_list_id: u64) -> Response<()>;
+        /// Adds a new value at the end of a custom list
+        fn custom_list_value_create(&self, project_template_id: u64, custom_list_id: u64, value: CustomListValueDto) -> Response<CustomListValueDto>;
+        /// Updates a value of a custom list, the values being saved together with their list
+        fn custom_list_value_update(&self, project_template_id: u64, custom_list_id: u64, value: CustomListValueDto) -> Response<()>;
+        /// Deactivates a value of a custom list, Spira does not allow deleting them
+        fn custom_list_value_deactivate(&self, project_template_id: u64, custom_list_id: u64, custom_property_value_id: u64) -> Response<()>;
+    }
+}
+
+impl<'a> TaskClient<'a> {
+    /// Retrieves a task, applies the changes and updates it, retrying on conflicts
+    /// with the current copy of the task up to max_retries times
+    pub fn update_with_retry<F>(
+        &self,
+        project_id: u64,
+        task_id: u64,
+        max_retries: u32,
+        apply: F,
+    ) -> Response<()>
+    where
+        F: FnMut(&mut TaskDto),
+    {
+        self.runtime.block_on(
+            self.inner
+                .update_with_retry(project_id, task_id, max_retries, apply),
+        )
+    }
+}
+
+impl<'a> IncidentClient<'a> {
+    /// Retrieves an incident, applies the changes and updates it, retrying on conflicts
+    /// with the current copy of the incident up to max_retries times
+    pub fn update_with_retry<F>(
+        &self,
+        project_id: u64,
+        incident_id: u64,
+        max_retries: u32,
+        apply: F,
+    ) -> Response<()>
+    where
+        F: FnMut(&mut IncidentDto),
+    {
+        self.runtime.block_on(self.inner.update_with_retry(
+            project_id,
+            incident_id,
+            max_retries,
+            apply,
+        ))
+    }
+}
+
+impl<'a> RequirementClient<'a> {
+    /// Retrieves a requirement, applies the changes and updates it, retrying on conflicts
+    /// with the current copy of the requirement up to max_retries times
+    pub fn update_with_retry<F>(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+        max_retries: u32,
+        apply: F,
+    ) -> Response<()>
+    where
+        F: FnMut(&mut RequirementDto),
+    {
+        self.runtime.block_on(self.inner.update_with_retry(
+            project_id,
+            requirement_id,
+            max_retries,
+            apply,
+        ))
+    }
+}
+
+impl<'a> ReleaseClient<'a> {
+    /// Retrieves a release, applies the changes and updates it, retrying on conflicts
+    /// with the current copy of the release up to max_retries times
+    pub fn update_with_retry<F>(
+        &self,
+        project_id: u64,
+        release_id: u64,
+        max_retries: u32,
+        apply: F,
+    ) -> Response<()>
+    where
+        F: FnMut(&mut ReleaseDto),
+    {
+        self.runtime.block_on(self.inner.update_with_retry(
+            project_id,
+            release_id,
+            max_retries,
+            apply,
+        ))
+    }
+}
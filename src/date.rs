@@ -0,0 +1,20 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Parses the date part of a Spira date-time, e.g. "2022-03-14T11:28:07.240Z"
+pub(crate) fn parse_date(value: &str) -> Option<NaiveDate> {
+    value
+        .get(..10)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+}
+
+/// Parses a Spira date-time, the values without an offset being in UTC,
+/// e.g. "2022-03-14T11:28:07.240Z" or "2022-03-14T11:28:07.240"
+pub(crate) fn parse_date_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|date_time| date_time.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                .map(|date_time| Utc.from_utc_datetime(&date_time))
+        })
+        .ok()
+}
@@ -0,0 +1,64 @@
+//! Errors
+//! The errors returned by the client besides the transport and serialization ones.
+
+use std::{error::Error, fmt};
+
+use crate::transport::StatusCode;
+
+/// Returned by the update methods when the artifact was modified by someone else since it
+/// was retrieved, Spira detecting it from the stale concurrency date of the sent copy.
+/// Holds the current copy of the artifact on the server
+#[derive(Debug)]
+pub struct ConflictError<T> {
+    pub current: T,
+}
+
+impl<T> fmt::Display for ConflictError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the artifact was modified since it was retrieved")
+    }
+}
+
+impl<T: fmt::Debug> Error for ConflictError<T> {}
+
+/// Returned for the payloads missing required fields, checked before anything is sent:
+/// by the batch operations, and by the updates of a payload without an id
+#[derive(Debug)]
+pub struct InvalidPayloadError {
+    pub missing_fields: Vec<&'static str>,
+}
+
+impl InvalidPayloadError {
+    /// The id of the payload, failing with the name of the id field when it is not set
+    pub(crate) fn require_id(id: Option<u64>, field_name: &'static str) -> Result<u64, Self> {
+        id.ok_or_else(|| InvalidPayloadError {
+            missing_fields: vec![field_name],
+        })
+    }
+}
+
+impl fmt::Display for InvalidPayloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the payload is missing the required fields: {}",
+            self.missing_fields.join(", ")
+        )
+    }
+}
+
+impl Error for InvalidPayloadError {}
+
+/// Returned for the responses with a client or server error status
+#[derive(Debug)]
+pub struct HttpStatusError {
+    pub status: StatusCode,
+}
+
+impl fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the server responded with the status {}", self.status)
+    }
+}
+
+impl Error for HttpStatusError {}
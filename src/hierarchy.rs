@@ -0,0 +1,372 @@
+//! Hierarchy helpers
+//! Spira returns hierarchical artifacts (releases, requirements) as a flat list where the
+//! position of each item is encoded in its indent level, a string made of three-letter
+//! segments: "AAA" is a root item, "AAAAAB" the second child of "AAA" and so on.
+//! Folders are returned with the id of their parent folder instead.
+
+use std::{cmp::Ordering, collections::HashMap};
+
+/// The number of characters making up one level of an indent level string
+const SEGMENT_LENGTH: usize = 3;
+
+/// Artifacts positioned in a hierarchy by their indent level
+pub trait Indented {
+    fn indent_level(&self) -> Option<&str>;
+}
+
+/// Artifacts positioned in a hierarchy by the id of their parent, like folders
+pub trait Parented {
+    fn id(&self) -> Option<u64>;
+    fn parent_id(&self) -> Option<u64>;
+}
+
+/// Returns the depth of an indent level, root items having a depth of 0
+pub fn depth(indent_level: &str) -> usize {
+    (indent_level.len() / SEGMENT_LENGTH).saturating_sub(1)
+}
+
+/// Returns the indent level of the parent item, or None for root items
+pub fn parent_indent_level(indent_level: &str) -> Option<&str> {
+    if indent_level.len() <= SEGMENT_LENGTH {
+        return None;
+    }
+
+    indent_level.get(..indent_level.len() - SEGMENT_LENGTH)
+}
+
+/// The index of a node inside a Tree
+pub type NodeId = usize;
+
+/// A single item of the tree with links to its parent and children
+#[derive(Debug)]
+pub struct Node<T> {
+    item: T,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+impl<T> Node<T> {
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+}
+
+/// An in-memory tree rebuilt from a flat list of artifacts
+#[derive(Debug)]
+pub struct Tree<T> {
+    nodes: Vec<Node<T>>,
+    roots: Vec<NodeId>,
+}
+
+impl<T: Indented> Tree<T> {
+    /// Builds the tree from a flat list of items. Items without an indent level or
+    /// whose parent is missing from the list are treated as root items
+    pub fn from_flat(mut items: Vec<T>) -> Self {
+        items.sort_by(|a, b| match (a.indent_level(), b.indent_level()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+
+        let mut tree = Tree {
+            nodes: Vec::with_capacity(items.len()),
+            roots: Vec::new(),
+        };
+        let mut by_indent_level: HashMap<String, NodeId> = HashMap::new();
+
+        for item in items {
+            let id = tree.nodes.len();
+            let indent_level = item.indent_level().map(str::to_owned);
+            let parent = indent_level
+                .as_deref()
+                .and_then(parent_indent_level)
+                .and_then(|parent| by_indent_level.get(parent).copied());
+
+            match parent {
+                Some(parent) => tree.nodes[parent].children.push(id),
+                None => tree.roots.push(id),
+            }
+            tree.nodes.push(Node {
+                item,
+                parent,
+                children: Vec::new(),
+            });
+
+            if let Some(indent_level) = indent_level {
+                by_indent_level.insert(indent_level, id);
+            }
+        }
+
+        tree
+    }
+}
+
+impl<T: Parented> Tree<T> {
+    /// Builds the tree from a flat list of items linked by their parent id. Items without
+    /// a parent id or whose parent is missing from the list are treated as root items,
+    /// as is one item of each parent cycle. Siblings keep the order of the list
+    pub fn from_parent_ids(items: Vec<T>) -> Self {
+        let by_id: HashMap<u64, usize> = items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| item.id().map(|id| (id, index)))
+            .collect();
+        let parents: Vec<Option<usize>> = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                item.parent_id()
+                    .and_then(|parent_id| by_id.get(&parent_id).copied())
+                    .filter(|parent| *parent != index)
+            })
+            .collect();
+
+        let mut children = vec![Vec::new(); items.len()];
+        let mut pending = Vec::new();
+        for (index, parent) in parents.iter().enumerate() {
+            match parent {
+                Some(parent) => children[*parent].push(index),
+                None => pending.push(index),
+            }
+        }
+
+        // walks the items parents first, the items left unvisited being part of a cycle
+        let mut visited = vec![false; items.len()];
+        let mut order = Vec::with_capacity(items.len());
+        let mut cycle_roots = Vec::new();
+        pending.reverse();
+        loop {
+            while let Some(index) = pending.pop() {
+                if !visited[index] {
+                    visited[index] = true;
+                    order.push(index);
+                    pending.extend(children[index].iter().rev());
+                }
+            }
+
+            match visited.iter().position(|visited| !visited) {
+                Some(index) => {
+                    cycle_roots.push(index);
+                    pending.push(index);
+                }
+                None => break,
+            }
+        }
+
+        let mut items: Vec<Option<T>> = items.into_iter().map(Some).collect();
+        let mut node_ids = vec![0; items.len()];
+        let mut tree = Tree {
+            nodes: Vec::with_capacity(items.len()),
+            roots: Vec::new(),
+        };
+
+        for index in order {
+            let id = tree.nodes.len();
+            node_ids[index] = id;
+            let parent = parents[index]
+                .filter(|_| !cycle_roots.contains(&index))
+                .map(|parent| node_ids[parent]);
+
+            match parent {
+                Some(parent) => tree.nodes[parent].children.push(id),
+                None => tree.roots.push(id),
+            }
+            tree.nodes.push(Node {
+                item: items[index].take().expect("each item is visited once"),
+                parent,
+                children: Vec::new(),
+            });
+        }
+
+        tree
+    }
+}
+
+impl<T> Tree<T> {
+    /// The top level nodes of the tree
+    pub fn roots(&self) -> &[NodeId] {
+        &self.roots
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&Node<T>> {
+        self.nodes.get(id)
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.node(id).map(Node::item)
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).and_then(Node::parent)
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        self.node(id).map(Node::children).unwrap_or_default()
+    }
+
+    /// Iterates over the ancestors of a node, starting with its direct parent
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(self.parent(id), move |id| self.parent(*id))
+    }
+
+    /// Iterates over all the nodes in hierarchy order (parents before their children)
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &T)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(id, node)| (id, &node.item))
+    }
+
+    /// Finds the first node, in hierarchy order, matching the predicate
+    pub fn find<P>(&self, mut predicate: P) -> Option<NodeId>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .find(|(_, item)| predicate(item))
+            .map(|(id, _)| id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Level(Option<&'static str>);
+
+    impl Indented for Level {
+        fn indent_level(&self) -> Option<&str> {
+            self.0
+        }
+    }
+
+    struct Folder {
+        id: u64,
+        parent_id: Option<u64>,
+    }
+
+    impl Parented for Folder {
+        fn id(&self) -> Option<u64> {
+            Some(self.id)
+        }
+
+        fn parent_id(&self) -> Option<u64> {
+            self.parent_id
+        }
+    }
+
+    fn folders(links: &[(u64, Option<u64>)]) -> Tree<Folder> {
+        Tree::from_parent_ids(
+            links
+                .iter()
+                .map(|&(id, parent_id)| Folder { id, parent_id })
+                .collect(),
+        )
+    }
+
+    fn folder_ids(tree: &Tree<Folder>, ids: &[NodeId]) -> Vec<u64> {
+        ids.iter().map(|id| tree.get(*id).unwrap().id).collect()
+    }
+
+    fn levels(tree: &Tree<Level>, ids: &[NodeId]) -> Vec<Option<&'static str>> {
+        ids.iter().map(|id| tree.get(*id).unwrap().0).collect()
+    }
+
+    #[test]
+    fn from_flat_sorts_out_of_order_items() {
+        let tree = Tree::from_flat(vec![
+            Level(Some("AAAAAB")),
+            Level(None),
+            Level(Some("AAB")),
+            Level(Some("AAA")),
+            Level(Some("AAAAAA")),
+        ]);
+
+        assert_eq!(
+            levels(&tree, tree.roots()),
+            vec![Some("AAA"), Some("AAB"), None]
+        );
+        let first = tree.roots()[0];
+        assert_eq!(
+            levels(&tree, tree.children(first)),
+            vec![Some("AAAAAA"), Some("AAAAAB")]
+        );
+        assert!(tree.children(tree.roots()[1]).is_empty());
+    }
+
+    #[test]
+    fn from_flat_makes_orphaned_levels_roots() {
+        let tree = Tree::from_flat(vec![
+            Level(Some("AAA")),
+            Level(Some("AAAAABAAA")),
+            Level(Some("AAAAABAAAAAA")),
+        ]);
+
+        assert_eq!(
+            levels(&tree, tree.roots()),
+            vec![Some("AAA"), Some("AAAAABAAA")]
+        );
+        let orphan = tree.roots()[1];
+        assert_eq!(
+            levels(&tree, tree.children(orphan)),
+            vec![Some("AAAAABAAAAAA")]
+        );
+        assert!(tree.children(tree.roots()[0]).is_empty());
+    }
+
+    #[test]
+    fn from_parent_ids_breaks_cycles_keeping_their_branches() {
+        // 1 -> 2 -> 3 -> 1 with 4 hanging off 2 and 5 off 4
+        let tree = folders(&[
+            (1, Some(3)),
+            (2, Some(1)),
+            (3, Some(2)),
+            (4, Some(2)),
+            (5, Some(4)),
+        ]);
+
+        assert_eq!(tree.len(), 5);
+        assert_eq!(folder_ids(&tree, tree.roots()), vec![1]);
+        let first = tree.roots()[0];
+        assert_eq!(folder_ids(&tree, tree.children(first)), vec![2]);
+        let second = tree.children(first)[0];
+        assert_eq!(folder_ids(&tree, tree.children(second)), vec![3, 4]);
+        let fourth = tree.children(second)[1];
+        assert_eq!(folder_ids(&tree, tree.children(fourth)), vec![5]);
+        assert_eq!(tree.ancestors(tree.children(fourth)[0]).count(), 3);
+    }
+
+    #[test]
+    fn from_parent_ids_makes_self_parents_roots() {
+        let tree = folders(&[(1, Some(1)), (2, Some(1))]);
+
+        assert_eq!(folder_ids(&tree, tree.roots()), vec![1]);
+        assert_eq!(folder_ids(&tree, tree.children(tree.roots()[0])), vec![2]);
+    }
+
+    #[test]
+    fn from_parent_ids_makes_orphans_roots() {
+        let tree = folders(&[(2, Some(9)), (1, None), (3, Some(2))]);
+
+        assert_eq!(folder_ids(&tree, tree.roots()), vec![2, 1]);
+        assert_eq!(folder_ids(&tree, tree.children(tree.roots()[0])), vec![3]);
+        assert_eq!(tree.parent(tree.roots()[0]), None);
+    }
+}
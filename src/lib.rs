@@ -1,18 +1,30 @@
 //! SpiraClient
 //! Inflectra Spira Rest client implementation in rust
 
+pub mod analytics;
+pub mod artifact;
+pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod error;
+pub mod hierarchy;
+pub mod lookup;
 pub mod resources;
+pub mod transport;
 
-use reqwest::{
-    self,
-    header::{HeaderMap, HeaderValue},
-    Client,
-};
+mod date;
+mod stream;
+
+use reqwest::{header::HeaderValue, Client};
 use resources::{
-    incident::IncidentClient, project::ProjectClient, project_template::ProjectTemplateClient,
-    release::ReleaseClient, requirement::RequirementClient, task::TaskClient, user::UserClient,
+    build::BuildClient, component::ComponentClient, history::HistoryClient,
+    incident::IncidentClient, program::ProgramClient, project::ProjectClient,
+    project_role::ProjectRoleClient, project_template::ProjectTemplateClient,
+    release::ReleaseClient, requirement::RequirementClient, risk::RiskClient, task::TaskClient,
+    user::UserClient, workflow::WorkflowClient,
 };
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
+use transport::{HttpClient, ReqwestTransport, Transport};
 
 /// The main client, contains child clients for each resource type like:
 /// TaskClient, ProjectClient, UserClient
@@ -24,13 +36,21 @@ pub struct SpiraClient<'a> {
     pub incident: IncidentClient<'a>,
     pub release: ReleaseClient<'a>,
     pub project_template: ProjectTemplateClient<'a>,
+    pub build: BuildClient<'a>,
+    pub workflow: WorkflowClient<'a>,
+    pub component: ComponentClient<'a>,
+    pub project_role: ProjectRoleClient<'a>,
+    pub program: ProgramClient<'a>,
+    pub risk: RiskClient<'a>,
+    pub history: HistoryClient<'a>,
 }
 
 type Response<T> = Result<T, Box<dyn std::error::Error>>;
 
 /// ## Usage Example
-/// ```rust
+/// ```rust,no_run
 /// use spira::{resources::project::ProjectDto, SpiraClient};
+/// use std::env;
 ///
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -48,24 +68,52 @@ type Response<T> = Result<T, Box<dyn std::error::Error>>;
 /// ```
 impl<'a> SpiraClient<'a> {
     pub fn new(base_url: &'a str, api_key: &str, username: &str) -> Response<Self> {
-        let mut headers = HeaderMap::new();
-        headers.insert("Accept", HeaderValue::from_str("application/json")?);
-        headers.insert("Content-type", HeaderValue::from_str("application/json")?);
-        headers.insert("api-key", HeaderValue::from_str(api_key)?);
-        headers.insert("username", HeaderValue::from_str(username)?);
-
         let client = Client::builder()
             .connect_timeout(Duration::from_secs(10))
-            .default_headers(headers)
             .build()?;
 
+        Self::with_transport(
+            base_url,
+            api_key,
+            username,
+            Arc::new(ReqwestTransport::new(client)),
+        )
+    }
+
+    /// Builds the client on top of another transport than reqwest, see the transport module
+    pub fn with_transport(
+        base_url: &'a str,
+        api_key: &str,
+        username: &str,
+        transport: Arc<dyn Transport>,
+    ) -> Response<Self> {
+        let mut headers = Vec::new();
+        for (name, value) in [
+            ("Accept", "application/json"),
+            ("Content-type", "application/json"),
+            ("api-key", api_key),
+            ("username", username),
+        ] {
+            HeaderValue::from_str(value)?;
+            headers.push((name.to_string(), value.to_string()));
+        }
+
+        let client = HttpClient::new(transport, headers);
+
         let task = TaskClient::new(client.clone(), base_url);
         let project = ProjectClient::new(client.clone(), base_url);
         let requirement = RequirementClient::new(client.clone(), base_url);
         let incident = IncidentClient::new(client.clone(), base_url);
         let user = UserClient::new(client.clone(), base_url);
         let release = ReleaseClient::new(client.clone(), base_url);
-        let project_template = ProjectTemplateClient::new(client, base_url);
+        let project_template = ProjectTemplateClient::new(client.clone(), base_url);
+        let build = BuildClient::new(client.clone(), base_url);
+        let workflow = WorkflowClient::new(client.clone(), base_url);
+        let component = ComponentClient::new(client.clone(), base_url);
+        let project_role = ProjectRoleClient::new(client.clone(), base_url);
+        let program = ProgramClient::new(client.clone(), base_url);
+        let risk = RiskClient::new(client.clone(), base_url);
+        let history = HistoryClient::new(client, base_url);
 
         Ok(SpiraClient {
             incident,
@@ -75,6 +123,13 @@ impl<'a> SpiraClient<'a> {
             user,
             release,
             project_template,
+            build,
+            workflow,
+            component,
+            project_role,
+            program,
+            risk,
+            history,
         })
     }
 }
@@ -0,0 +1,317 @@
+//! ProjectLookup
+//! Resolves the ids found on artifacts (priorities, statuses, users, releases...) to their
+//! names and back, loading each list lazily and caching it for a configurable time.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+use crate::{Response, SpiraClient};
+
+/// How long a loaded list is reused before being fetched again
+const DEFAULT_TTL: Duration = Duration::from_secs(300);
+
+/// The kind of list used to resolve an id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LookupKind {
+    IncidentStatus,
+    IncidentPriority,
+    IncidentSeverity,
+    IncidentType,
+    TaskStatus,
+    TaskType,
+    TaskPriority,
+    RequirementStatus,
+    /// Requirement types, including packages which use the -1 id, see
+    /// ProjectLookup::requirement_type_name_of
+    RequirementType,
+    RequirementImportance,
+    User,
+    Release,
+    Component,
+    RiskStatus,
+    RiskType,
+    RiskProbability,
+    RiskImpact,
+}
+
+/// The ids are stored signed as the package requirement type uses the -1 id
+struct CachedList {
+    loaded_at: Instant,
+    entries: Vec<(i64, String)>,
+}
+
+/// Lazily loaded, cached id and name lists of a single project
+pub struct ProjectLookup<'a> {
+    spira: &'a SpiraClient<'a>,
+    project_id: u64,
+    project_template_id: u64,
+    ttl: Duration,
+    cache: Mutex<HashMap<LookupKind, CachedList>>,
+}
+
+/// ## Usage Example
+/// ```rust,no_run
+/// use spira::{lookup::{LookupKind, ProjectLookup}, SpiraClient};
+///
+/// # async fn run(spira_client: SpiraClient<'_>) -> Result<(), Box<dyn std::error::Error>> {
+/// let lookup = ProjectLookup::new(&spira_client, 100 /* project_id */, 1 /* project_template_id */);
+/// let incident = spira_client.incident.get(100, 1500).await?;
+///
+/// if let Some(priority_id) = incident.priority_id {
+///     println!("{:?}", lookup.name_of(LookupKind::IncidentPriority, priority_id).await?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+impl<'a> ProjectLookup<'a> {
+    pub fn new(spira: &'a SpiraClient<'a>, project_id: u64, project_template_id: u64) -> Self {
+        ProjectLookup {
+            spira,
+            project_id,
+            project_template_id,
+            ttl: DEFAULT_TTL,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets how long a loaded list is reused before being fetched again
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Resolves an id to its name
+    pub async fn name_of(&self, kind: LookupKind, id: u64) -> Response<Option<String>> {
+        match i64::try_from(id) {
+            Ok(id) => self.signed_name_of(kind, id).await,
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Resolves a name, ignoring its case, to its id
+    pub async fn id_of(&self, kind: LookupKind, name: &str) -> Response<Option<u64>> {
+        let id = self.signed_id_of(kind, name).await?;
+
+        Ok(id.and_then(|id| u64::try_from(id).ok()))
+    }
+
+    /// Resolves a requirement type id, as found on RequirementDto, to its name.
+    /// Unlike name_of it also resolves the -1 id of packages
+    pub async fn requirement_type_name_of(
+        &self,
+        requirement_type_id: i64,
+    ) -> Response<Option<String>> {
+        self.signed_name_of(LookupKind::RequirementType, requirement_type_id)
+            .await
+    }
+
+    /// Resolves a requirement type name, ignoring its case, to its id, -1 for packages
+    pub async fn requirement_type_id_of(&self, name: &str) -> Response<Option<i64>> {
+        self.signed_id_of(LookupKind::RequirementType, name).await
+    }
+
+    /// Fetches a list again, regardless of its age
+    pub async fn refresh(&self, kind: LookupKind) -> Response<()> {
+        let entries = self.load(kind).await?;
+        self.lock().insert(
+            kind,
+            CachedList {
+                loaded_at: Instant::now(),
+                entries,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Drops all the cached lists, they will be fetched again on their next use
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    async fn signed_name_of(&self, kind: LookupKind, id: i64) -> Response<Option<String>> {
+        self.with_entries(kind, |entries| {
+            entries
+                .iter()
+                .find(|(entry_id, _)| *entry_id == id)
+                .map(|(_, name)| name.clone())
+        })
+        .await
+    }
+
+    async fn signed_id_of(&self, kind: LookupKind, name: &str) -> Response<Option<i64>> {
+        let name = name.to_lowercase();
+
+        self.with_entries(kind, |entries| {
+            entries
+                .iter()
+                .find(|(_, entry_name)| entry_name.to_lowercase() == name)
+                .map(|(id, _)| *id)
+        })
+        .await
+    }
+
+    async fn with_entries<R>(
+        &self,
+        kind: LookupKind,
+        resolve: impl FnOnce(&[(i64, String)]) -> R,
+    ) -> Response<R> {
+        let is_fresh = self
+            .lock()
+            .get(&kind)
+            .map(|cached| cached.loaded_at.elapsed() < self.ttl)
+            .unwrap_or(false);
+
+        if !is_fresh {
+            self.refresh(kind).await?;
+        }
+
+        let cache = self.lock();
+        let entries = cache
+            .get(&kind)
+            .map(|cached| cached.entries.as_slice())
+            .unwrap_or_default();
+
+        Ok(resolve(entries))
+    }
+
+    async fn load(&self, kind: LookupKind) -> Response<Vec<(i64, String)>> {
+        let template = &self.spira.project_template;
+        let template_id = self.project_template_id;
+
+        let entries = match kind {
+            LookupKind::IncidentStatus => template
+                .incident_status_list(template_id)
+                .await?
+                .into_iter()
+                .map(|status| (signed(status.incident_status_id), status.name))
+                .collect::<Vec<_>>(),
+            LookupKind::IncidentPriority => template
+                .incident_priority_list(template_id)
+                .await?
+                .into_iter()
+                .map(|priority| (signed(priority.priority_id), priority.name))
+                .collect(),
+            LookupKind::IncidentSeverity => template
+                .incident_severity_list(template_id)
+                .await?
+                .into_iter()
+                .map(|severity| (signed(severity.severity_id), severity.name))
+                .collect(),
+            LookupKind::IncidentType => template
+                .incident_type_list(template_id)
+                .await?
+                .into_iter()
+                .map(|incident_type| (signed(incident_type.incident_type_id), incident_type.name))
+                .collect(),
+            LookupKind::TaskStatus => template
+                .task_status_list(template_id)
+                .await?
+                .into_iter()
+                .map(|status| (signed(status.task_status_id), status.name))
+                .collect(),
+            LookupKind::TaskType => template
+                .task_type_list(template_id)
+                .await?
+                .into_iter()
+                .map(|task_type| (signed(task_type.task_type_id), task_type.name))
+                .collect(),
+            LookupKind::TaskPriority => template
+                .task_priority_list(template_id)
+                .await?
+                .into_iter()
+                .map(|priority| (signed(priority.priority_id), priority.name))
+                .collect(),
+            LookupKind::RequirementStatus => template
+                .requirement_status_list(template_id)
+                .await?
+                .into_iter()
+                .map(|status| (signed(status.requirement_status_id), status.name))
+                .collect(),
+            LookupKind::RequirementType => template
+                .requirement_type_list(template_id)
+                .await?
+                .into_iter()
+                .map(|requirement_type| {
+                    (requirement_type.requirement_type_id, requirement_type.name)
+                })
+                .collect(),
+            LookupKind::RequirementImportance => template
+                .requirement_importance_list(template_id)
+                .await?
+                .into_iter()
+                .map(|importance| (signed(importance.importance_id), importance.name))
+                .collect(),
+            LookupKind::User => self
+                .spira
+                .user
+                .list(self.project_id)
+                .await?
+                .into_iter()
+                .map(|user| (signed(user.user_id), user.full_name))
+                .collect(),
+            LookupKind::Release => self
+                .spira
+                .release
+                .list(self.project_id)
+                .await?
+                .into_iter()
+                .map(|release| {
+                    (
+                        signed(release.release_id),
+                        release.full_name.or(Some(release.name)),
+                    )
+                })
+                .collect(),
+            LookupKind::Component => self
+                .spira
+                .component
+                .list(self.project_id, false, false)
+                .await?
+                .into_iter()
+                .map(|component| (signed(component.component_id), Some(component.name)))
+                .collect(),
+            LookupKind::RiskStatus => template
+                .risk_status_list(template_id)
+                .await?
+                .into_iter()
+                .map(|status| (signed(status.risk_status_id), status.name))
+                .collect(),
+            LookupKind::RiskType => template
+                .risk_type_list(template_id)
+                .await?
+                .into_iter()
+                .map(|risk_type| (signed(risk_type.risk_type_id), risk_type.name))
+                .collect(),
+            LookupKind::RiskProbability => template
+                .risk_probability_list(template_id)
+                .await?
+                .into_iter()
+                .map(|probability| (signed(probability.risk_probability_id), probability.name))
+                .collect(),
+            LookupKind::RiskImpact => template
+                .risk_impact_list(template_id)
+                .await?
+                .into_iter()
+                .map(|impact| (signed(impact.risk_impact_id), impact.name))
+                .collect(),
+        };
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|(id, name)| Some((id?, name?)))
+            .collect())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<LookupKind, CachedList>> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Converts an id to the signed ids stored in the cache
+fn signed(id: Option<u64>) -> Option<i64> {
+    id.and_then(|id| i64::try_from(id).ok())
+}
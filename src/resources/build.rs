@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::{transport::HttpClient, Response};
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The Build fields
+pub struct BuildDto {
+    /// The id of the build (integer)
+    #[serde(rename = "BuildId")]
+    pub build_id: Option<u64>,
+
+    /// The id of the status of the build (integer)
+    /// Relevant values: Failed 1; Succeeded 2; Unstable 3; Aborted 4
+    #[serde(rename = "BuildStatusId")]
+    pub build_status_id: u64,
+
+    /// The id of the project that the build belongs to (integer)
+    #[serde(rename = "ProjectId")]
+    pub project_id: Option<u64>,
+
+    /// The id of the release or iteration that the build belongs to (integer)
+    #[serde(rename = "ReleaseId")]
+    pub release_id: Option<u64>,
+
+    /// The name of the build (string)
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    /// The full description of the build, typically the CI console output (string)
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+
+    /// The date/time that the build was originally created If no value is provided,
+    /// the current date/time on the server is used (date-time)
+    #[serde(rename = "CreationDate")]
+    pub creation_date: Option<String>,
+
+    /// The date/time that the build was last modified (date-time)
+    #[serde(rename = "LastUpdateDate")]
+    pub last_update_date: Option<String>,
+
+    /// The list of source code revisions associated with the build
+    #[serde(rename = "Revisions")]
+    pub revisions: Option<Vec<BuildSourceCodeDto>>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// A source code revision linked to a build
+pub struct BuildSourceCodeDto {
+    /// The id of the build the revision belongs to (integer)
+    #[serde(rename = "BuildId")]
+    pub build_id: Option<u64>,
+
+    /// The key that identifies the revision in the source code provider,
+    /// e.g. the commit hash (string)
+    #[serde(rename = "RevisionKey")]
+    pub revision_key: String,
+
+    /// The date/time that the revision was associated with the build (date-time)
+    #[serde(rename = "CreationDate")]
+    pub creation_date: Option<String>,
+}
+
+/// The Build Artifact Submodule
+pub struct BuildClient<'a> {
+    client: HttpClient,
+    base_url: &'a str,
+}
+
+impl<'a> BuildClient<'a> {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
+        BuildClient { client, base_url }
+    }
+
+    /// Retrieves all the builds belonging to a release or iteration
+    pub async fn list(&self, project_id: u64, release_id: u64) -> Response<Vec<BuildDto>> {
+        let path = &format!("/projects/{}/releases/{}/builds", project_id, release_id);
+
+        let builds = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<BuildDto>>()
+            .await?;
+
+        Ok(builds)
+    }
+
+    /// Retrieves a single build, including its linked source code revisions
+    pub async fn get(&self, project_id: u64, release_id: u64, build_id: u64) -> Response<BuildDto> {
+        let path = &format!(
+            "/projects/{}/releases/{}/builds/{}",
+            project_id, release_id, build_id
+        );
+
+        let build = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<BuildDto>()
+            .await?;
+
+        Ok(build)
+    }
+
+    /// Records a new build, with its source code revisions, against a release or iteration
+    pub async fn create(
+        &self,
+        project_id: u64,
+        release_id: u64,
+        build: BuildDto,
+    ) -> Response<BuildDto> {
+        let json_build = serde_json::to_string(&build)?;
+        let build = self
+            .client
+            .post(self.append_to_url(&format!(
+                "/projects/{}/releases/{}/builds",
+                project_id, release_id
+            )))
+            .body(json_build)
+            .send()
+            .await?
+            .json::<BuildDto>()
+            .await?;
+
+        Ok(build)
+    }
+
+    fn append_to_url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::{error::InvalidPayloadError, transport::HttpClient, Response};
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The Component fields
+pub struct ComponentDto {
+    /// The id of the component (integer)
+    #[serde(rename = "ComponentId")]
+    pub component_id: Option<u64>,
+
+    /// The id of the project that the component belongs to (integer)
+    #[serde(rename = "ProjectId")]
+    pub project_id: Option<u64>,
+
+    /// The name of the component (string - required for POST)
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    /// Is the component active, inactive components can't be used on new artifacts (boolean)
+    #[serde(rename = "IsActive")]
+    pub is_active: Option<bool>,
+
+    /// Has the component been deleted (boolean - read only)
+    #[serde(rename = "IsDeleted")]
+    pub is_deleted: Option<bool>,
+}
+
+/// The Component Submodule
+pub struct ComponentClient<'a> {
+    client: HttpClient,
+    base_url: &'a str,
+}
+
+impl<'a> ComponentClient<'a> {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
+        ComponentClient { client, base_url }
+    }
+
+    /// Retrieves the components of a project
+    pub async fn list(
+        &self,
+        project_id: u64,
+        active_only: bool,
+        include_deleted: bool,
+    ) -> Response<Vec<ComponentDto>> {
+        let path = &format!(
+            "/projects/{}/components?active_only={}&include_deleted={}",
+            project_id, active_only, include_deleted
+        );
+
+        let components = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<ComponentDto>>()
+            .await?;
+
+        Ok(components)
+    }
+
+    /// Retrieves a single component
+    pub async fn get(&self, project_id: u64, component_id: u64) -> Response<ComponentDto> {
+        let path = &format!("/projects/{}/components/{}", project_id, component_id);
+
+        let component = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<ComponentDto>()
+            .await?;
+
+        Ok(component)
+    }
+
+    /// Finds the active component with the given name, ignoring its case
+    pub async fn find_by_name(
+        &self,
+        project_id: u64,
+        name: &str,
+    ) -> Response<Option<ComponentDto>> {
+        let name = name.to_lowercase();
+        let component = self
+            .list(project_id, true, false)
+            .await?
+            .into_iter()
+            .find(|component| component.name.to_lowercase() == name);
+
+        Ok(component)
+    }
+
+    /// Creates a new component in the project
+    pub async fn create(&self, project_id: u64, component: ComponentDto) -> Response<ComponentDto> {
+        let json_component = serde_json::to_string(&component)?;
+        let component = self
+            .client
+            .post(self.append_to_url(&format!("/projects/{}/components", project_id)))
+            .body(json_component)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ComponentDto>()
+            .await?;
+
+        Ok(component)
+    }
+
+    /// Updates a component
+    pub async fn update(&self, project_id: u64, component: ComponentDto) -> Response<()> {
+        let component_id = InvalidPayloadError::require_id(component.component_id, "ComponentId")?;
+        let json_component = serde_json::to_string(&component)?;
+        self.client
+            .put(self.append_to_url(&format!(
+                "/projects/{}/components/{}",
+                project_id, component_id
+            )))
+            .body(json_component)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Deactivates a component, keeping it on the artifacts already using it
+    pub async fn deactivate(&self, project_id: u64, component_id: u64) -> Response<()> {
+        let mut component = self.get(project_id, component_id).await?;
+        component.is_active = Some(false);
+
+        self.update(project_id, component).await
+    }
+
+    /// Deletes a component
+    pub async fn delete(&self, project_id: u64, component_id: u64) -> Response<()> {
+        self.client
+            .delete(self.append_to_url(&format!(
+                "/projects/{}/components/{}",
+                project_id, component_id
+            )))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    fn append_to_url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
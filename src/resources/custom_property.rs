@@ -0,0 +1,721 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+/// The definition of a custom property in a project template
+pub struct CustomPropertyDto {
+    /// The id of the custom property (integer)
+    #[serde(rename = "CustomPropertyId")]
+    pub custom_property_id: Option<u64>,
+
+    /// The id of the project template the custom property belongs to (integer)
+    #[serde(rename = "ProjectTemplateId")]
+    pub project_template_id: Option<u64>,
+
+    /// The id of the artifact type the custom property applies to (integer)
+    #[serde(rename = "ArtifactTypeId")]
+    pub artifact_type_id: Option<u64>,
+
+    /// The number of the custom property field on the artifact, 1 for Custom_01 (integer)
+    #[serde(rename = "PropertyNumber")]
+    pub property_number: Option<u64>,
+
+    /// The display name of the custom property (string)
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    /// The id of the type of the custom property (integer)
+    /// Relevant values: Text 1; Integer 2; Decimal 3; Boolean 4; Date 5; List 6; MultiList 7; User 8
+    #[serde(rename = "CustomPropertyTypeId")]
+    pub custom_property_type_id: Option<u64>,
+
+    /// The name of the type of the custom property (string)
+    #[serde(rename = "CustomPropertyTypeName")]
+    pub custom_property_type_name: Option<String>,
+
+    /// The custom list providing the values of List and MultiList properties
+    #[serde(rename = "CustomList")]
+    pub custom_list: Option<CustomListDto>,
+
+    /// The options of the custom property, e.g. whether it allows empty values
+    #[serde(rename = "Options")]
+    pub options: Option<Vec<CustomPropertyOptionDto>>,
+
+    /// Has the custom property been deleted (boolean)
+    #[serde(rename = "IsDeleted")]
+    pub is_deleted: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+/// An option of a custom property definition
+pub struct CustomPropertyOptionDto {
+    /// The id of the option (integer)
+    /// Relevant values: AllowEmpty 1; MaxLength 2; MinLength 3; RichText 4; Default 5;
+    /// MaxValue 6; MinValue 7; Precision 8
+    #[serde(rename = "CustomPropertyOptionId")]
+    pub custom_property_option_id: Option<u64>,
+
+    /// The value of the option (string)
+    #[serde(rename = "Value")]
+    pub value: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+/// A custom list, the set of values available to List and MultiList custom properties
+pub struct CustomListDto {
+    /// The id of the custom list (integer)
+    #[serde(rename = "CustomPropertyListId")]
+    pub custom_property_list_id: Option<u64>,
+
+    /// The id of the project template the custom list belongs to (integer)
+    #[serde(rename = "ProjectTemplateId")]
+    pub project_template_id: Option<u64>,
+
+    /// The name of the custom list (string)
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    /// Is the custom list active (boolean)
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+
+    /// Are the values sorted by name rather than by id (boolean)
+    #[serde(rename = "SortedOnValue")]
+    pub sorted_on_value: Option<bool>,
+
+    /// The values of the custom list
+    #[serde(rename = "Values")]
+    pub values: Option<Vec<CustomListValueDto>>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+/// A value of a custom list
+pub struct CustomListValueDto {
+    /// The id of the custom list value (integer)
+    #[serde(rename = "CustomPropertyValueId")]
+    pub custom_property_value_id: Option<u64>,
+
+    /// The id of the custom list the value belongs to (integer)
+    #[serde(rename = "CustomPropertyListId")]
+    pub custom_property_list_id: Option<u64>,
+
+    /// The display name of the value (string)
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    /// Is the value active (boolean)
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+/// The value of a custom property on an artifact, only the field matching the
+/// custom property type is set
+pub struct ArtifactCustomPropertyDto {
+    /// The number of the custom property field, matching CustomPropertyDto::property_number
+    #[serde(rename = "PropertyNumber")]
+    pub property_number: u64,
+
+    /// The value of Text properties
+    #[serde(rename = "StringValue")]
+    pub string_value: Option<String>,
+
+    /// The value of Integer, List and User properties
+    #[serde(rename = "IntegerValue")]
+    pub integer_value: Option<i64>,
+
+    /// The value of Boolean properties
+    #[serde(rename = "BooleanValue")]
+    pub boolean_value: Option<bool>,
+
+    /// The value of Date properties (date-time)
+    #[serde(rename = "DateTimeValue")]
+    pub date_time_value: Option<String>,
+
+    /// The value of Decimal properties
+    #[serde(rename = "DecimalValue")]
+    pub decimal_value: Option<f64>,
+
+    /// The value of MultiList properties
+    #[serde(rename = "IntegerListValue")]
+    pub integer_list_value: Option<Vec<u64>>,
+}
+
+/// The type of a custom property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomPropertyType {
+    Text,
+    Integer,
+    Decimal,
+    Boolean,
+    Date,
+    List,
+    MultiList,
+    User,
+}
+
+impl CustomPropertyType {
+    pub fn from_id(id: u64) -> Option<Self> {
+        let property_type = match id {
+            1 => CustomPropertyType::Text,
+            2 => CustomPropertyType::Integer,
+            3 => CustomPropertyType::Decimal,
+            4 => CustomPropertyType::Boolean,
+            5 => CustomPropertyType::Date,
+            6 => CustomPropertyType::List,
+            7 => CustomPropertyType::MultiList,
+            8 => CustomPropertyType::User,
+            _ => return None,
+        };
+
+        Some(property_type)
+    }
+}
+
+impl CustomPropertyDto {
+    pub fn property_type(&self) -> Option<CustomPropertyType> {
+        self.custom_property_type_id
+            .and_then(CustomPropertyType::from_id)
+    }
+
+    fn is_list_value(&self, value_id: u64) -> bool {
+        match self
+            .custom_list
+            .as_ref()
+            .and_then(|list| list.values.as_ref())
+        {
+            Some(values) => values
+                .iter()
+                .any(|value| value.custom_property_value_id == Some(value_id)),
+            // Without the list values there is nothing to validate against
+            None => true,
+        }
+    }
+}
+
+/// A typed custom property value
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomPropertyValue {
+    Text(String),
+    Integer(i64),
+    Decimal(f64),
+    Boolean(bool),
+    /// A date-time, e.g. "2022-03-14T11:28:07.240Z"
+    Date(String),
+    /// The id of the selected custom list value
+    List(u64),
+    /// The ids of the selected custom list values
+    MultiList(Vec<u64>),
+    /// The id of the selected user
+    User(u64),
+}
+
+impl CustomPropertyValue {
+    pub fn property_type(&self) -> CustomPropertyType {
+        match self {
+            CustomPropertyValue::Text(_) => CustomPropertyType::Text,
+            CustomPropertyValue::Integer(_) => CustomPropertyType::Integer,
+            CustomPropertyValue::Decimal(_) => CustomPropertyType::Decimal,
+            CustomPropertyValue::Boolean(_) => CustomPropertyType::Boolean,
+            CustomPropertyValue::Date(_) => CustomPropertyType::Date,
+            CustomPropertyValue::List(_) => CustomPropertyType::List,
+            CustomPropertyValue::MultiList(_) => CustomPropertyType::MultiList,
+            CustomPropertyValue::User(_) => CustomPropertyType::User,
+        }
+    }
+
+    fn read(property_type: CustomPropertyType, dto: &ArtifactCustomPropertyDto) -> Option<Self> {
+        let value = match property_type {
+            CustomPropertyType::Text => CustomPropertyValue::Text(dto.string_value.clone()?),
+            CustomPropertyType::Integer => CustomPropertyValue::Integer(dto.integer_value?),
+            CustomPropertyType::Decimal => CustomPropertyValue::Decimal(dto.decimal_value?),
+            CustomPropertyType::Boolean => CustomPropertyValue::Boolean(dto.boolean_value?),
+            CustomPropertyType::Date => CustomPropertyValue::Date(dto.date_time_value.clone()?),
+            CustomPropertyType::List => {
+                CustomPropertyValue::List(u64::try_from(dto.integer_value?).ok()?)
+            }
+            CustomPropertyType::MultiList => {
+                CustomPropertyValue::MultiList(dto.integer_list_value.clone()?)
+            }
+            CustomPropertyType::User => {
+                CustomPropertyValue::User(u64::try_from(dto.integer_value?).ok()?)
+            }
+        };
+
+        Some(value)
+    }
+
+    fn write(self, dto: &mut ArtifactCustomPropertyDto) -> Result<(), CustomPropertyError> {
+        match self {
+            CustomPropertyValue::Text(value) => dto.string_value = Some(value),
+            CustomPropertyValue::Integer(value) => dto.integer_value = Some(value),
+            CustomPropertyValue::Decimal(value) => dto.decimal_value = Some(value),
+            CustomPropertyValue::Boolean(value) => dto.boolean_value = Some(value),
+            CustomPropertyValue::Date(value) => dto.date_time_value = Some(value),
+            CustomPropertyValue::List(value) | CustomPropertyValue::User(value) => {
+                let integer_value =
+                    i64::try_from(value).map_err(|_| CustomPropertyError::ValueOutOfRange {
+                        property_number: dto.property_number,
+                        value,
+                    })?;
+                dto.integer_value = Some(integer_value)
+            }
+            CustomPropertyValue::MultiList(value) => dto.integer_list_value = Some(value),
+        }
+
+        Ok(())
+    }
+}
+
+/// The errors raised when reading or writing a custom property by name
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomPropertyError {
+    /// No definition has the given name
+    UnknownProperty(String),
+    /// The definition has no property number or an unknown type
+    InvalidDefinition(String),
+    /// The value type does not match the type of the definition
+    TypeMismatch {
+        name: String,
+        expected: CustomPropertyType,
+        found: CustomPropertyType,
+    },
+    /// The value is not part of the custom list of the definition
+    InvalidListValue { name: String, value_id: u64 },
+    /// The list value or user id is too large to be sent as the integer value
+    ValueOutOfRange { property_number: u64, value: u64 },
+}
+
+impl fmt::Display for CustomPropertyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CustomPropertyError::UnknownProperty(name) => {
+                write!(f, "unknown custom property \"{}\"", name)
+            }
+            CustomPropertyError::InvalidDefinition(name) => {
+                write!(f, "invalid definition for custom property \"{}\"", name)
+            }
+            CustomPropertyError::TypeMismatch {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "custom property \"{}\" expects a {:?} value, got {:?}",
+                name, expected, found
+            ),
+            CustomPropertyError::InvalidListValue { name, value_id } => write!(
+                f,
+                "value {} is not part of the list of custom property \"{}\"",
+                value_id, name
+            ),
+            CustomPropertyError::ValueOutOfRange {
+                property_number,
+                value,
+            } => write!(
+                f,
+                "value {} of custom property {} is out of the integer range",
+                value, property_number
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CustomPropertyError {}
+
+/// Artifacts holding custom property values, giving typed access to them by their display
+/// name using the custom property definitions of the project template
+pub trait CustomProperties {
+    fn custom_properties(&self) -> Option<&Vec<ArtifactCustomPropertyDto>>;
+
+    fn custom_properties_mut(&mut self) -> &mut Option<Vec<ArtifactCustomPropertyDto>>;
+
+    /// Reads the value of a custom property by its display name
+    fn custom_property(
+        &self,
+        definitions: &[CustomPropertyDto],
+        name: &str,
+    ) -> Result<Option<CustomPropertyValue>, CustomPropertyError> {
+        let (_, property_number, property_type) = find_definition(definitions, name)?;
+
+        let value = self
+            .custom_properties()
+            .and_then(|properties| {
+                properties
+                    .iter()
+                    .find(|property| property.property_number == property_number)
+            })
+            .and_then(|property| CustomPropertyValue::read(property_type, property));
+
+        Ok(value)
+    }
+
+    /// Sets, or clears when None, the value of a custom property by its display name,
+    /// validating it against the definition
+    fn set_custom_property(
+        &mut self,
+        definitions: &[CustomPropertyDto],
+        name: &str,
+        value: Option<CustomPropertyValue>,
+    ) -> Result<(), CustomPropertyError> {
+        let (definition, property_number, property_type) = find_definition(definitions, name)?;
+
+        if let Some(value) = &value {
+            if value.property_type() != property_type {
+                return Err(CustomPropertyError::TypeMismatch {
+                    name: name.to_owned(),
+                    expected: property_type,
+                    found: value.property_type(),
+                });
+            }
+
+            let list_value_ids = match value {
+                CustomPropertyValue::List(value_id) => std::slice::from_ref(value_id),
+                CustomPropertyValue::MultiList(value_ids) => value_ids.as_slice(),
+                _ => &[],
+            };
+            if let Some(value_id) = list_value_ids
+                .iter()
+                .find(|value_id| !definition.is_list_value(**value_id))
+            {
+                return Err(CustomPropertyError::InvalidListValue {
+                    name: name.to_owned(),
+                    value_id: *value_id,
+                });
+            }
+        }
+
+        self.set_custom_property_value(property_number, value)
+    }
+
+    /// Sets, or clears when None, the value of a custom property by its number,
+    /// without validating it against a definition. Only fails, leaving the artifact
+    /// untouched, on the ids out of the integer range
+    fn set_custom_property_value(
+        &mut self,
+        property_number: u64,
+        value: Option<CustomPropertyValue>,
+    ) -> Result<(), CustomPropertyError> {
+        let mut property = ArtifactCustomPropertyDto {
+            property_number,
+            ..Default::default()
+        };
+        if let Some(value) = value {
+            value.write(&mut property)?;
+        }
+
+        let properties = self.custom_properties_mut().get_or_insert_with(Vec::new);
+        match properties
+            .iter_mut()
+            .find(|existing| existing.property_number == property_number)
+        {
+            Some(existing) => *existing = property,
+            None => properties.push(property),
+        }
+
+        Ok(())
+    }
+}
+
+fn find_definition<'d>(
+    definitions: &'d [CustomPropertyDto],
+    name: &str,
+) -> Result<(&'d CustomPropertyDto, u64, CustomPropertyType), CustomPropertyError> {
+    let definition = definitions
+        .iter()
+        .find(|definition| {
+            definition.is_deleted != Some(true) && definition.name.as_deref() == Some(name)
+        })
+        .ok_or_else(|| CustomPropertyError::UnknownProperty(name.to_owned()))?;
+
+    match (definition.property_number, definition.property_type()) {
+        (Some(property_number), Some(property_type)) => {
+            Ok((definition, property_number, property_type))
+        }
+        _ => Err(CustomPropertyError::InvalidDefinition(name.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Artifact {
+        custom_properties: Option<Vec<ArtifactCustomPropertyDto>>,
+    }
+
+    impl CustomProperties for Artifact {
+        fn custom_properties(&self) -> Option<&Vec<ArtifactCustomPropertyDto>> {
+            self.custom_properties.as_ref()
+        }
+
+        fn custom_properties_mut(&mut self) -> &mut Option<Vec<ArtifactCustomPropertyDto>> {
+            &mut self.custom_properties
+        }
+    }
+
+    fn definition(name: &str, property_number: u64, type_id: u64) -> CustomPropertyDto {
+        CustomPropertyDto {
+            name: Some(name.to_string()),
+            property_number: Some(property_number),
+            custom_property_type_id: Some(type_id),
+            ..Default::default()
+        }
+    }
+
+    fn list_definition(
+        name: &str,
+        property_number: u64,
+        type_id: u64,
+        value_ids: &[u64],
+    ) -> CustomPropertyDto {
+        let values = value_ids
+            .iter()
+            .map(|value_id| CustomListValueDto {
+                custom_property_value_id: Some(*value_id),
+                ..Default::default()
+            })
+            .collect();
+
+        CustomPropertyDto {
+            custom_list: Some(CustomListDto {
+                values: Some(values),
+                ..Default::default()
+            }),
+            ..definition(name, property_number, type_id)
+        }
+    }
+
+    fn definitions() -> Vec<CustomPropertyDto> {
+        vec![
+            CustomPropertyDto {
+                is_deleted: Some(true),
+                ..definition("Notes", 9, 2)
+            },
+            definition("Notes", 1, 1),
+            definition("Points", 2, 2),
+            definition("Ratio", 3, 3),
+            definition("Blocking", 4, 4),
+            definition("Due", 5, 5),
+            list_definition("Color", 6, 6, &[10, 11]),
+            list_definition("Tags", 7, 7, &[20, 21]),
+            definition("Reviewer", 8, 8),
+            definition("Unlisted", 10, 6),
+            CustomPropertyDto {
+                is_deleted: Some(true),
+                ..definition("Old", 11, 1)
+            },
+            CustomPropertyDto {
+                property_number: None,
+                ..definition("Broken", 12, 1)
+            },
+            definition("Unknown type", 13, 42),
+        ]
+    }
+
+    #[test]
+    fn round_trips_every_type() {
+        let definitions = definitions();
+        let values = [
+            ("Notes", CustomPropertyValue::Text("note".to_string())),
+            ("Points", CustomPropertyValue::Integer(-3)),
+            ("Ratio", CustomPropertyValue::Decimal(0.5)),
+            ("Blocking", CustomPropertyValue::Boolean(true)),
+            (
+                "Due",
+                CustomPropertyValue::Date("2022-03-14T11:28:07.240Z".to_string()),
+            ),
+            ("Color", CustomPropertyValue::List(11)),
+            ("Tags", CustomPropertyValue::MultiList(vec![20, 21])),
+            ("Reviewer", CustomPropertyValue::User(5)),
+        ];
+        let mut artifact = Artifact::default();
+
+        for (name, value) in &values {
+            artifact
+                .set_custom_property(&definitions, name, Some(value.clone()))
+                .unwrap();
+        }
+
+        for (name, value) in &values {
+            assert_eq!(
+                artifact
+                    .custom_property(&definitions, name)
+                    .unwrap()
+                    .as_ref(),
+                Some(value)
+            );
+        }
+        assert_eq!(artifact.custom_properties().unwrap().len(), values.len());
+    }
+
+    #[test]
+    fn clearing_replaces_the_value() {
+        let definitions = definitions();
+        let mut artifact = Artifact::default();
+        let value = CustomPropertyValue::Text("note".to_string());
+
+        artifact
+            .set_custom_property(&definitions, "Notes", Some(value))
+            .unwrap();
+        artifact
+            .set_custom_property(&definitions, "Notes", None)
+            .unwrap();
+
+        assert_eq!(
+            artifact.custom_property(&definitions, "Notes").unwrap(),
+            None
+        );
+        assert_eq!(artifact.custom_properties().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn rejects_mismatched_types() {
+        let mut artifact = Artifact::default();
+
+        let error = artifact
+            .set_custom_property(
+                &definitions(),
+                "Points",
+                Some(CustomPropertyValue::Boolean(true)),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            error,
+            CustomPropertyError::TypeMismatch {
+                name: "Points".to_string(),
+                expected: CustomPropertyType::Integer,
+                found: CustomPropertyType::Boolean,
+            }
+        );
+        assert!(artifact.custom_properties().is_none());
+    }
+
+    #[test]
+    fn rejects_values_missing_from_the_list() {
+        let definitions = definitions();
+        let mut artifact = Artifact::default();
+
+        let error = artifact
+            .set_custom_property(&definitions, "Color", Some(CustomPropertyValue::List(20)))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            CustomPropertyError::InvalidListValue {
+                name: "Color".to_string(),
+                value_id: 20,
+            }
+        );
+
+        let error = artifact
+            .set_custom_property(
+                &definitions,
+                "Tags",
+                Some(CustomPropertyValue::MultiList(vec![20, 11])),
+            )
+            .unwrap_err();
+        assert_eq!(
+            error,
+            CustomPropertyError::InvalidListValue {
+                name: "Tags".to_string(),
+                value_id: 11,
+            }
+        );
+
+        // without the list values there is nothing to check against
+        artifact
+            .set_custom_property(
+                &definitions,
+                "Unlisted",
+                Some(CustomPropertyValue::List(99)),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn skips_deleted_definitions() {
+        let definitions = definitions();
+        let mut artifact = Artifact::default();
+
+        assert_eq!(
+            artifact.custom_property(&definitions, "Old").unwrap_err(),
+            CustomPropertyError::UnknownProperty("Old".to_string())
+        );
+
+        // the deleted Notes definition is an Integer one numbered 9
+        artifact
+            .set_custom_property(
+                &definitions,
+                "Notes",
+                Some(CustomPropertyValue::Text("note".to_string())),
+            )
+            .unwrap();
+        assert_eq!(artifact.custom_properties().unwrap()[0].property_number, 1);
+    }
+
+    #[test]
+    fn rejects_invalid_definitions() {
+        let definitions = definitions();
+        let artifact = Artifact::default();
+
+        for name in ["Broken", "Unknown type"] {
+            assert_eq!(
+                artifact.custom_property(&definitions, name).unwrap_err(),
+                CustomPropertyError::InvalidDefinition(name.to_string())
+            );
+        }
+        assert_eq!(
+            artifact
+                .custom_property(&definitions, "Missing")
+                .unwrap_err(),
+            CustomPropertyError::UnknownProperty("Missing".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_ids_out_of_the_integer_range() {
+        let mut artifact = Artifact::default();
+
+        let error = artifact
+            .set_custom_property(
+                &definitions(),
+                "Reviewer",
+                Some(CustomPropertyValue::User(u64::MAX)),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            error,
+            CustomPropertyError::ValueOutOfRange {
+                property_number: 8,
+                value: u64::MAX,
+            }
+        );
+        assert!(artifact.custom_properties().is_none());
+    }
+
+    #[test]
+    fn ignores_negative_list_values() {
+        let artifact = Artifact {
+            custom_properties: Some(vec![ArtifactCustomPropertyDto {
+                property_number: 6,
+                integer_value: Some(-1),
+                ..Default::default()
+            }]),
+        };
+
+        assert_eq!(
+            artifact.custom_property(&definitions(), "Color").unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::{artifact::ArtifactType, date::parse_date_time, transport::HttpClient, Response};
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+/// A set of changes made to an artifact by a user at the same time
+pub struct HistoryChangeSetDto {
+    /// The id of the change set (integer)
+    #[serde(rename = "HistoryChangeSetId")]
+    pub history_change_set_id: Option<u64>,
+
+    /// The id of the user who made the changes (integer)
+    #[serde(rename = "UserId")]
+    pub user_id: Option<u64>,
+
+    /// The full name of the user who made the changes (string)
+    #[serde(rename = "UserFullName")]
+    pub user_full_name: Option<String>,
+
+    /// The id of the artifact type (integer)
+    #[serde(rename = "ArtifactTypeId")]
+    pub artifact_type_id: Option<u64>,
+
+    /// The id of the artifact (integer)
+    #[serde(rename = "ArtifactId")]
+    pub artifact_id: Option<u64>,
+
+    /// The name of the artifact at the time of the change (string)
+    #[serde(rename = "ArtifactDesc")]
+    pub artifact_desc: Option<String>,
+
+    /// The date/time that the changes were made
+    /// { "ChangeDate": "2022-03-14T11:28:07.240Z" },
+    #[serde(rename = "ChangeDate")]
+    pub change_date: Option<String>,
+
+    /// The id of the type of change (integer), see HistoryChangeSetDto::change_type
+    /// Relevant values: Modified 1; Deleted 2; Added 3; Purged 4; Rollback 5; Undelete 6;
+    /// Imported 7; Exported 8; Association Add 13; Association Remove 14; Association Modify 15
+    #[serde(rename = "ChangeTypeId")]
+    pub change_type_id: Option<u64>,
+
+    /// The name of the type of change (string)
+    #[serde(rename = "ChangeTypeName")]
+    pub change_type_name: Option<String>,
+
+    /// The field changes of the change set
+    #[serde(rename = "Details")]
+    pub details: Option<Vec<HistoryChangeDto>>,
+}
+
+/// The type of change recorded by a change set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeType {
+    Modified,
+    Deleted,
+    Added,
+    Purged,
+    Rollback,
+    Undelete,
+    Imported,
+    Exported,
+    AssociationAdd,
+    AssociationRemove,
+    AssociationModify,
+}
+
+impl ChangeType {
+    pub fn id(&self) -> u64 {
+        match self {
+            ChangeType::Modified => 1,
+            ChangeType::Deleted => 2,
+            ChangeType::Added => 3,
+            ChangeType::Purged => 4,
+            ChangeType::Rollback => 5,
+            ChangeType::Undelete => 6,
+            ChangeType::Imported => 7,
+            ChangeType::Exported => 8,
+            ChangeType::AssociationAdd => 13,
+            ChangeType::AssociationRemove => 14,
+            ChangeType::AssociationModify => 15,
+        }
+    }
+
+    pub fn from_id(id: u64) -> Option<Self> {
+        let change_type = match id {
+            1 => ChangeType::Modified,
+            2 => ChangeType::Deleted,
+            3 => ChangeType::Added,
+            4 => ChangeType::Purged,
+            5 => ChangeType::Rollback,
+            6 => ChangeType::Undelete,
+            7 => ChangeType::Imported,
+            8 => ChangeType::Exported,
+            13 => ChangeType::AssociationAdd,
+            14 => ChangeType::AssociationRemove,
+            15 => ChangeType::AssociationModify,
+            _ => return None,
+        };
+
+        Some(change_type)
+    }
+}
+
+impl HistoryChangeSetDto {
+    pub fn change_type(&self) -> Option<ChangeType> {
+        self.change_type_id.and_then(ChangeType::from_id)
+    }
+
+    /// The date/time that the changes were made, none when missing or invalid
+    pub fn changed_on(&self) -> Option<DateTime<Utc>> {
+        self.change_date.as_deref().and_then(parse_date_time)
+    }
+
+    /// The change made to a field in this change set, by field name (e.g. "IncidentStatusId")
+    pub fn change_of(&self, field_name: &str) -> Option<&HistoryChangeDto> {
+        self.details
+            .iter()
+            .flatten()
+            .find(|change| change.field_name.as_deref() == Some(field_name))
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+/// The change of a single field of an artifact
+pub struct HistoryChangeDto {
+    /// The id of the change (integer)
+    #[serde(rename = "ArtifactHistoryId")]
+    pub artifact_history_id: Option<u64>,
+
+    /// The name of the field that was changed (string)
+    #[serde(rename = "FieldName")]
+    pub field_name: Option<String>,
+
+    /// The display name of the field that was changed (string)
+    #[serde(rename = "FieldCaption")]
+    pub field_caption: Option<String>,
+
+    /// The display value before the change (string)
+    #[serde(rename = "OldValue")]
+    pub old_value: Option<String>,
+
+    /// The display value after the change (string)
+    #[serde(rename = "NewValue")]
+    pub new_value: Option<String>,
+
+    /// The value before the change, for the id fields (integer)
+    #[serde(rename = "OldValueInt")]
+    pub old_value_int: Option<i64>,
+
+    /// The value after the change, for the id fields (integer)
+    #[serde(rename = "NewValueInt")]
+    pub new_value_int: Option<i64>,
+
+    /// The value before the change, for the date fields (date-time)
+    #[serde(rename = "OldValueDate")]
+    pub old_value_date: Option<String>,
+
+    /// The value after the change, for the date fields (date-time)
+    #[serde(rename = "NewValueDate")]
+    pub new_value_date: Option<String>,
+
+    /// The id of the custom property, when a custom property was changed (integer)
+    #[serde(rename = "CustomPropertyId")]
+    pub custom_property_id: Option<u64>,
+}
+
+/// The History Submodule, the audit trail of the artifacts
+pub struct HistoryClient<'a> {
+    client: HttpClient,
+    base_url: &'a str,
+}
+
+impl<'a> HistoryClient<'a> {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
+        HistoryClient { client, base_url }
+    }
+
+    /// Retrieves the change history of an artifact, the most recent change set first
+    pub async fn list(
+        &self,
+        project_id: u64,
+        artifact_type: ArtifactType,
+        artifact_id: u64,
+    ) -> Response<Vec<HistoryChangeSetDto>> {
+        let path = &format!(
+            "/projects/{}/artifact-types/{}/artifacts/{}/history",
+            project_id,
+            artifact_type.id(),
+            artifact_id
+        );
+
+        let change_sets = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<HistoryChangeSetDto>>()
+            .await?;
+
+        Ok(change_sets)
+    }
+
+    fn append_to_url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
@@ -1,11 +1,20 @@
-use std::collections::HashMap;
-
-use reqwest::Client;
+use futures::Stream;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use serde_with::skip_serializing_none;
 
-use crate::Response;
+use crate::{
+    batch::{self, BatchOptions, BatchResults},
+    error::{ConflictError, InvalidPayloadError},
+    resources::custom_property::{
+        ArtifactCustomPropertyDto, CustomProperties, CustomPropertyError, CustomPropertyValue,
+    },
+    stream,
+    transport::{HttpClient, StatusCode},
+    Response,
+};
+
+/// The number of times a patch is retried on concurrency conflicts
+const PATCH_MAX_RETRIES: u32 = 3;
 
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -124,18 +133,102 @@ pub struct IncidentDto {
     #[serde(rename = "IsAttachments")]
     pub is_attachements: Option<bool>,
 
+    /// The values of the custom properties of the artifact
     #[serde(rename = "CustomProperties")]
-    pub custom_properties: Option<Vec<HashMap<String, Value>>>,
+    pub custom_properties: Option<Vec<ArtifactCustomPropertyDto>>,
+}
+
+impl CustomProperties for IncidentDto {
+    fn custom_properties(&self) -> Option<&Vec<ArtifactCustomPropertyDto>> {
+        self.custom_properties.as_ref()
+    }
+
+    fn custom_properties_mut(&mut self) -> &mut Option<Vec<ArtifactCustomPropertyDto>> {
+        &mut self.custom_properties
+    }
+}
+
+/// A change to a single field of an incident, applied by IncidentClient::patch
+#[derive(Debug, Clone)]
+pub enum IncidentChange {
+    /// The name of the incident
+    Name(String),
+    /// The description of the incident
+    Description(Option<String>),
+    /// The id of the status of the incident
+    Status(u64),
+    /// The id of the type of the incident
+    Type(u64),
+    /// The id of the priority of the incident
+    Priority(Option<u64>),
+    /// The id of the severity of the incident
+    Severity(Option<u64>),
+    /// The id of the user the incident is assigned to
+    Owner(Option<u64>),
+    /// The id of the release the incident was detected in
+    DetectedRelease(Option<u64>),
+    /// The id of the release the incident is resolved in
+    ResolvedRelease(Option<u64>),
+    /// The id of the release the incident resolution was verified in
+    VerifiedRelease(Option<u64>),
+    /// The ids of the components of the incident
+    Components(Vec<u64>),
+    /// The start date of the incident
+    StartDate(Option<String>),
+    /// The end date of the incident
+    EndDate(Option<String>),
+    /// The estimated effort in minutes
+    EstimatedEffort(Option<u64>),
+    /// The actual effort in minutes
+    ActualEffort(Option<u64>),
+    /// The remaining effort in minutes
+    RemainingEffort(Option<u64>),
+    /// Sets, or clears when None, the value of a custom property by its number
+    CustomProperty {
+        property_number: u64,
+        value: Option<CustomPropertyValue>,
+    },
+}
+
+impl IncidentChange {
+    /// Applies the change to the incident, failing on the custom property values
+    /// that can't be sent
+    pub fn apply(&self, incident: &mut IncidentDto) -> Result<(), CustomPropertyError> {
+        match self {
+            IncidentChange::Name(value) => incident.name = value.clone(),
+            IncidentChange::Description(value) => incident.description = value.clone(),
+            IncidentChange::Status(value) => incident.incident_status_id = Some(*value),
+            IncidentChange::Type(value) => incident.incident_type_id = Some(*value),
+            IncidentChange::Priority(value) => incident.priority_id = *value,
+            IncidentChange::Severity(value) => incident.severity_id = *value,
+            IncidentChange::Owner(value) => incident.owner_id = *value,
+            IncidentChange::DetectedRelease(value) => incident.detected_release_id = *value,
+            IncidentChange::ResolvedRelease(value) => incident.resolved_release_id = *value,
+            IncidentChange::VerifiedRelease(value) => incident.verified_release_id = *value,
+            IncidentChange::Components(value) => incident.component_ids = Some(value.clone()),
+            IncidentChange::StartDate(value) => incident.start_date = value.clone(),
+            IncidentChange::EndDate(value) => incident.end_date = value.clone(),
+            IncidentChange::EstimatedEffort(value) => incident.estimated_effort = *value,
+            IncidentChange::ActualEffort(value) => incident.actual_effort = *value,
+            IncidentChange::RemainingEffort(value) => incident.remaining_effort = *value,
+            IncidentChange::CustomProperty {
+                property_number,
+                value,
+            } => return incident.set_custom_property_value(*property_number, value.clone()),
+        }
+
+        Ok(())
+    }
 }
 
 /// The Requirement Artifact Submodule
 pub struct IncidentClient<'a> {
-    client: Client,
+    client: HttpClient,
     base_url: &'a str,
 }
 
 impl<'a> IncidentClient<'a> {
-    pub fn new(client: Client, base_url: &'a str) -> Self {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
         IncidentClient { client, base_url }
     }
 
@@ -152,6 +245,12 @@ impl<'a> IncidentClient<'a> {
         Ok(incidents)
     }
 
+    /// Streams the incidents owned by the currently authenticated user, deserializing
+    /// each one as soon as it is received
+    pub fn list_my_stream(&self) -> impl Stream<Item = Response<IncidentDto>> {
+        stream::json_array(self.client.get(self.append_to_url("/incidents")))
+    }
+
     /// Retrieves a single incident in the system
     pub async fn get(&self, project_id: u64, incident_id: u64) -> Response<IncidentDto> {
         let path = &format!("/projects/{}/incidents/{}", project_id, incident_id);
@@ -176,28 +275,90 @@ impl<'a> IncidentClient<'a> {
             .body(json_incident)
             .send()
             .await?
+            .error_for_status()?
             .json::<IncidentDto>()
             .await?;
 
         Ok(incident)
     }
 
-    /// Updates an incident in the system
+    /// Updates an incident in the system. When the incident was modified since it was retrieved,
+    /// fails with a ConflictError holding the current copy of the incident
     pub async fn update(&self, project_id: u64, incident: IncidentDto) -> Response<()> {
+        let incident_id = InvalidPayloadError::require_id(incident.incident_id, "IncidentId")?;
         let json_incident = serde_json::to_string(&incident)?;
-        self.client
+        let response = self
+            .client
             .put(self.append_to_url(&format!(
                 "/projects/{}/incidents/{}",
-                project_id,
-                incident.incident_id.unwrap()
+                project_id, incident_id
             )))
             .body(json_incident)
             .send()
             .await?;
 
+        if response.status() == StatusCode::CONFLICT {
+            let current = self.get(project_id, incident_id).await?;
+            return Err(Box::new(ConflictError { current }));
+        }
+        response.error_for_status()?;
+
         Ok(())
     }
 
+    /// Retrieves an incident, applies the changes and updates it, retrying on conflicts
+    /// with the current copy of the incident up to max_retries times
+    pub async fn update_with_retry<F>(
+        &self,
+        project_id: u64,
+        incident_id: u64,
+        max_retries: u32,
+        mut apply: F,
+    ) -> Response<()>
+    where
+        F: FnMut(&mut IncidentDto),
+    {
+        let mut incident = self.get(project_id, incident_id).await?;
+        let mut retries = 0;
+
+        loop {
+            apply(&mut incident);
+
+            match self.update(project_id, incident).await {
+                Err(error) if retries < max_retries => {
+                    incident = error.downcast::<ConflictError<IncidentDto>>()?.current;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Applies the changes to the latest version of the incident on the server, leaving
+    /// its other fields untouched. Concurrency conflicts are retried on the fresh copy
+    pub async fn patch(
+        &self,
+        project_id: u64,
+        incident_id: u64,
+        changes: &[IncidentChange],
+    ) -> Response<()> {
+        // the changes only fail on the custom property values that can't be sent,
+        // whatever the incident they are applied to
+        let mut checked = IncidentDto::default();
+        for change in changes {
+            change.apply(&mut checked)?;
+        }
+
+        self.update_with_retry(project_id, incident_id, PATCH_MAX_RETRIES, |incident| {
+            for change in changes {
+                change
+                    .apply(incident)
+                    .expect("the changes were checked before the update");
+            }
+        })
+        .await
+    }
+
     /// Deletes an incident in the system
     pub async fn delete(&self, project_id: u64, incident_id: u64) -> Response<()> {
         self.client
@@ -206,12 +367,80 @@ impl<'a> IncidentClient<'a> {
                 project_id, incident_id
             )))
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
 
         Ok(())
     }
 
+    /// Creates the incidents of the batch, the results holding the ids assigned by the server
+    pub async fn create_batch(
+        &self,
+        project_id: u64,
+        incidents: Vec<IncidentDto>,
+        options: &BatchOptions,
+    ) -> BatchResults {
+        batch::run(incidents, options, |incident| async move {
+            batch::validate(&incident, missing_fields(&incident, false))?;
+            if options.dry_run {
+                return Ok(None);
+            }
+
+            Ok(self.create(project_id, incident).await?.incident_id)
+        })
+        .await
+    }
+
+    /// Updates the incidents of the batch
+    pub async fn update_batch(
+        &self,
+        project_id: u64,
+        incidents: Vec<IncidentDto>,
+        options: &BatchOptions,
+    ) -> BatchResults {
+        batch::run(incidents, options, |incident| async move {
+            batch::validate(&incident, missing_fields(&incident, true))?;
+            let incident_id = incident.incident_id;
+            if !options.dry_run {
+                self.update(project_id, incident).await?;
+            }
+
+            Ok(incident_id)
+        })
+        .await
+    }
+
+    /// Deletes the incidents of the batch
+    pub async fn delete_batch(
+        &self,
+        project_id: u64,
+        incident_ids: Vec<u64>,
+        options: &BatchOptions,
+    ) -> BatchResults {
+        batch::run(incident_ids, options, |incident_id| async move {
+            if !options.dry_run {
+                self.delete(project_id, incident_id).await?;
+            }
+
+            Ok(Some(incident_id))
+        })
+        .await
+    }
+
     fn append_to_url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
 }
+
+/// The fields Spira requires on an incident, the id being required on updates
+fn missing_fields(incident: &IncidentDto, update: bool) -> Vec<&'static str> {
+    let mut missing_fields = Vec::new();
+    if incident.name.is_empty() {
+        missing_fields.push("Name");
+    }
+    if update && incident.incident_id.is_none() {
+        missing_fields.push("IncidentId");
+    }
+
+    missing_fields
+}
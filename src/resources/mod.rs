@@ -1,3 +1,7 @@
+/// Build Rest Client
+pub mod build;
+/// Custom Properties shared by the artifacts
+pub mod custom_property;
 /// Incident Rest Client
 pub mod incident;
 /// Project Rest Client
@@ -9,7 +13,19 @@ pub mod task;
 /// User Rest Client
 pub mod user;
 
+/// Component Rest Client
+pub mod component;
+/// History Rest Client
+pub mod history;
+/// Program Rest Client
+pub mod program;
+/// ProjectRole Rest Client
+pub mod project_role;
 /// ProjectTemplate Rest Client
 pub mod project_template;
 /// Release Rest Client
 pub mod release;
+/// Risk Rest Client
+pub mod risk;
+/// Workflow Rest Client
+pub mod workflow;
@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::{
+    hierarchy::Indented,
+    resources::project::{ProjectClient, ProjectDto},
+    transport::HttpClient,
+    Response,
+};
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The Program fields, a program being a group of related projects
+pub struct ProgramDto {
+    /// The id of the program, matching ProjectDto::project_group_id (integer)
+    #[serde(rename = "ProgramId")]
+    pub program_id: Option<u64>,
+
+    /// The id of the portfolio the program belongs to (integer)
+    #[serde(rename = "PortfolioId")]
+    pub portfolio_id: Option<u64>,
+
+    /// The id of the template used by default by the projects of the program (integer)
+    #[serde(rename = "ProjectTemplateId")]
+    pub project_template_id: Option<u64>,
+
+    /// The name of the program (string)
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    /// The description of the program (string)
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+
+    /// The url of the program website (string)
+    #[serde(rename = "Website")]
+    pub website: Option<String>,
+
+    /// Is the program active (boolean)
+    #[serde(rename = "IsActive")]
+    pub is_active: Option<bool>,
+
+    /// Is this the default program new projects are added to (boolean)
+    #[serde(rename = "IsDefault")]
+    pub is_default: Option<bool>,
+
+    /// The date/time that the program was originally created
+    /// { "CreationDate": "2022-03-14T11:28:07.240Z" },
+    #[serde(rename = "CreationDate")]
+    pub creation_date: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The Program Milestone fields, the program level releases
+pub struct ProgramMilestoneDto {
+    /// The id of the milestone (integer)
+    #[serde(rename = "MilestoneId")]
+    pub milestone_id: Option<u64>,
+
+    /// The id of the program the milestone belongs to (integer)
+    #[serde(rename = "ProgramId")]
+    pub program_id: Option<u64>,
+
+    /// The name of the milestone (string)
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    /// The description of the milestone (string)
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+
+    /// The id of the status of the milestone (integer)
+    #[serde(rename = "StatusId")]
+    pub status_id: Option<u64>,
+
+    /// The id of the type of the milestone (integer)
+    #[serde(rename = "TypeId")]
+    pub type_id: Option<u64>,
+
+    /// The id of the user that the milestone is assigned-to (integer)
+    #[serde(rename = "OwnerId")]
+    pub owner_id: Option<u64>,
+
+    /// The date that the milestone starts on (date-time)
+    #[serde(rename = "StartDate")]
+    pub start_date: Option<String>,
+
+    /// The date that the milestone ends on (date-time)
+    #[serde(rename = "EndDate")]
+    pub end_date: Option<String>,
+
+    /// The completion percentage of the milestone (integer)
+    #[serde(rename = "PercentComplete")]
+    pub percent_complete: Option<u64>,
+
+    /// The date/time that the milestone was last modified
+    #[serde(rename = "LastUpdateDate")]
+    pub last_update_date: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The Program Capability fields, the program level requirements
+pub struct ProgramCapabilityDto {
+    /// The id of the capability (integer)
+    #[serde(rename = "CapabilityId")]
+    pub capability_id: Option<u64>,
+
+    /// The id of the program the capability belongs to (integer)
+    #[serde(rename = "ProgramId")]
+    pub program_id: Option<u64>,
+
+    /// The id of the milestone the capability is scheduled for (integer)
+    #[serde(rename = "MilestoneId")]
+    pub milestone_id: Option<u64>,
+
+    /// The indentation level of the artifact (string) The system uses a set of
+    /// three-letter segments to denote indent (e.g. AAA followed by AAB, etc.)
+    #[serde(rename = "IndentLevel")]
+    pub indent_level: Option<String>,
+
+    /// The name of the capability (string)
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    /// The description of the capability (string)
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+
+    /// The id of the status of the capability (integer)
+    #[serde(rename = "StatusId")]
+    pub status_id: Option<u64>,
+
+    /// The id of the type of the capability (integer)
+    #[serde(rename = "TypeId")]
+    pub type_id: Option<u64>,
+
+    /// The id of the priority of the capability (integer)
+    #[serde(rename = "PriorityId")]
+    pub priority_id: Option<u64>,
+
+    /// The id of the user that the capability is assigned-to (integer)
+    #[serde(rename = "OwnerId")]
+    pub owner_id: Option<u64>,
+
+    /// The date/time that the capability was last modified
+    #[serde(rename = "LastUpdateDate")]
+    pub last_update_date: Option<String>,
+}
+
+impl Indented for ProgramCapabilityDto {
+    fn indent_level(&self) -> Option<&str> {
+        self.indent_level.as_deref()
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The Portfolio fields, a portfolio being a group of programs
+pub struct PortfolioDto {
+    /// The id of the portfolio (integer)
+    #[serde(rename = "PortfolioId")]
+    pub portfolio_id: Option<u64>,
+
+    /// The name of the portfolio (string)
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    /// The description of the portfolio (string)
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+
+    /// Is the portfolio active (boolean)
+    #[serde(rename = "IsActive")]
+    pub is_active: Option<bool>,
+}
+
+/// The Program and Portfolio Submodule
+pub struct ProgramClient<'a> {
+    client: HttpClient,
+    base_url: &'a str,
+}
+
+impl<'a> ProgramClient<'a> {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
+        ProgramClient { client, base_url }
+    }
+
+    /// Retrieves all the programs the current user has access to
+    pub async fn list(&self) -> Response<Vec<ProgramDto>> {
+        let programs = self
+            .client
+            .get(self.append_to_url("/programs"))
+            .send()
+            .await?
+            .json::<Vec<ProgramDto>>()
+            .await?;
+
+        Ok(programs)
+    }
+
+    /// Retrieves a single program
+    pub async fn get(&self, program_id: u64) -> Response<ProgramDto> {
+        let program = self
+            .client
+            .get(self.append_to_url(&format!("/programs/{}", program_id)))
+            .send()
+            .await?
+            .json::<ProgramDto>()
+            .await?;
+
+        Ok(program)
+    }
+
+    /// Filters the projects the current user has access to down to the ones of the program.
+    /// Spira has no endpoint listing the projects of a program, so this fetches all of them
+    pub async fn project_list(&self, program_id: u64) -> Response<Vec<ProjectDto>> {
+        let projects = ProjectClient::new(self.client.clone(), self.base_url)
+            .list()
+            .await?
+            .into_iter()
+            .filter(|project| project.project_group_id == Some(program_id))
+            .collect();
+
+        Ok(projects)
+    }
+
+    /// Retrieves the milestones of a program
+    pub async fn milestone_list(&self, program_id: u64) -> Response<Vec<ProgramMilestoneDto>> {
+        let milestones = self
+            .client
+            .get(self.append_to_url(&format!("/programs/{}/milestones", program_id)))
+            .send()
+            .await?
+            .json::<Vec<ProgramMilestoneDto>>()
+            .await?;
+
+        Ok(milestones)
+    }
+
+    /// Retrieves the capabilities of a program, in hierarchy order
+    pub async fn capability_list(&self, program_id: u64) -> Response<Vec<ProgramCapabilityDto>> {
+        let capabilities = self
+            .client
+            .get(self.append_to_url(&format!("/programs/{}/capabilities", program_id)))
+            .send()
+            .await?
+            .json::<Vec<ProgramCapabilityDto>>()
+            .await?;
+
+        Ok(capabilities)
+    }
+
+    /// Retrieves all the portfolios
+    pub async fn portfolio_list(&self) -> Response<Vec<PortfolioDto>> {
+        let portfolios = self
+            .client
+            .get(self.append_to_url("/portfolios"))
+            .send()
+            .await?
+            .json::<Vec<PortfolioDto>>()
+            .await?;
+
+        Ok(portfolios)
+    }
+
+    /// Retrieves the programs of a portfolio
+    pub async fn portfolio_program_list(&self, portfolio_id: u64) -> Response<Vec<ProgramDto>> {
+        let programs = self
+            .list()
+            .await?
+            .into_iter()
+            .filter(|program| program.portfolio_id == Some(portfolio_id))
+            .collect();
+
+        Ok(programs)
+    }
+
+    fn append_to_url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
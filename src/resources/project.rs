@@ -1,23 +1,99 @@
-use reqwest::Client;
+use futures::Stream;
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 
-use crate::Response;
+use crate::{error::InvalidPayloadError, stream, transport::HttpClient, Response};
 
+#[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Default)]
+/// The Project fields
 pub struct ProjectDto {
-    #[serde(rename = "Name")]
-    pub name: Option<String>,
+    /// The id of the project (integer)
     #[serde(rename = "ProjectId")]
     pub project_id: Option<u64>,
+
+    /// The id of the template the project uses for its workflows, types and
+    /// custom properties (integer)
+    #[serde(rename = "ProjectTemplateId")]
+    pub project_template_id: Option<u64>,
+
+    /// The id of the program (project group) the project belongs to (integer)
+    #[serde(rename = "ProjectGroupId")]
+    pub project_group_id: Option<u64>,
+
+    /// The name of the project (string - required for POST)
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    /// The description of the project (string)
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+
+    /// The url of the project website (string)
+    #[serde(rename = "Website")]
+    pub website: Option<String>,
+
+    /// Is the project active (boolean)
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+
+    /// The date/time that the project was originally created
+    /// { "CreationDate": "2022-03-14T11:28:07.240Z" },
+    #[serde(rename = "CreationDate")]
+    pub creation_date: Option<String>,
+
+    /// The number of hours in a working day (integer)
+    #[serde(rename = "WorkingHours")]
+    pub working_hours: Option<u64>,
+
+    /// The number of days in a working week (integer)
+    #[serde(rename = "WorkingDays")]
+    pub working_days: Option<u64>,
+
+    /// The number of non-working hours in a standard month (integer)
+    #[serde(rename = "NonWorkingHours")]
+    pub non_working_hours: Option<u64>,
+
+    /// Is time tracking enabled for incidents (boolean)
+    #[serde(rename = "TimeTrackingIncidents")]
+    pub time_tracking_incidents: Option<bool>,
+
+    /// Is time tracking enabled for tasks (boolean)
+    #[serde(rename = "TimeTrackingTasks")]
+    pub time_tracking_tasks: Option<bool>,
+
+    /// Is the incident effort included in the release and iteration totals (boolean)
+    #[serde(rename = "EffortIncidents")]
+    pub effort_incidents: Option<bool>,
+
+    /// Is the task effort included in the release and iteration totals (boolean)
+    #[serde(rename = "EffortTasks")]
+    pub effort_tasks: Option<bool>,
+
+    /// Is the test case effort included in the release and iteration totals (boolean)
+    #[serde(rename = "EffortTestCases")]
+    pub effort_test_cases: Option<bool>,
+
+    /// The default effort (in minutes) of new tasks (integer)
+    #[serde(rename = "TaskDefaultEffort")]
+    pub task_default_effort: Option<u64>,
+
+    /// The default estimate (in story points) of new requirements (decimal)
+    #[serde(rename = "ReqDefaultEstimate")]
+    pub req_default_estimate: Option<f32>,
+
+    /// The number of minutes of effort in one story point (integer)
+    #[serde(rename = "ReqPointEffort")]
+    pub req_point_effort: Option<u64>,
 }
 
 pub struct ProjectClient<'a> {
-    client: Client,
+    client: HttpClient,
     base_url: &'a str,
 }
 
 impl<'a> ProjectClient<'a> {
-    pub fn new(client: Client, base_url: &'a str) -> Self {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
         ProjectClient { client, base_url }
     }
 
@@ -33,6 +109,93 @@ impl<'a> ProjectClient<'a> {
         Ok(projects)
     }
 
+    /// Streams the projects, deserializing each one as soon as it is received
+    pub fn list_stream(&self) -> impl Stream<Item = Response<ProjectDto>> {
+        stream::json_array(self.client.get(self.append_to_url("/projects")))
+    }
+
+    /// Retrieves a single project
+    pub async fn get(&self, project_id: u64) -> Response<ProjectDto> {
+        let project = self
+            .client
+            .get(self.append_to_url(&format!("/projects/{}", project_id)))
+            .send()
+            .await?
+            .json::<ProjectDto>()
+            .await?;
+
+        Ok(project)
+    }
+
+    /// Creates a new project. When an existing project is provided, its configuration and
+    /// artifacts are copied into the new project, otherwise the project uses the template
+    /// set on `project_template_id` or the default one
+    pub async fn create(
+        &self,
+        project: ProjectDto,
+        existing_project_id: Option<u64>,
+    ) -> Response<ProjectDto> {
+        let mut path = String::from("/projects");
+        if let Some(existing_project_id) = existing_project_id {
+            path.push_str(&format!("?existing_project_id={}", existing_project_id));
+        }
+
+        let json_project = serde_json::to_string(&project)?;
+        let project = self
+            .client
+            .post(self.append_to_url(&path))
+            .body(json_project)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ProjectDto>()
+            .await?;
+
+        Ok(project)
+    }
+
+    /// Updates a project
+    pub async fn update(&self, project: ProjectDto) -> Response<()> {
+        let project_id = InvalidPayloadError::require_id(project.project_id, "ProjectId")?;
+        let json_project = serde_json::to_string(&project)?;
+        self.client
+            .put(self.append_to_url(&format!("/projects/{}", project_id)))
+            .body(json_project)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Deletes a project with all its artifacts
+    pub async fn delete(&self, project_id: u64) -> Response<()> {
+        self.client
+            .delete(self.append_to_url(&format!("/projects/{}", project_id)))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Recalculates the task progress and test execution status of the project releases,
+    /// or of a single release when one is provided
+    pub async fn refresh_progress(&self, project_id: u64, release_id: Option<u64>) -> Response<()> {
+        let mut path = format!("/projects/{}/refresh", project_id);
+        if let Some(release_id) = release_id {
+            path.push_str(&format!("?release_id={}", release_id));
+        }
+
+        self.client
+            .post(self.append_to_url(&path))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
     fn append_to_url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
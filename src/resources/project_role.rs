@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::{artifact::ArtifactType, transport::HttpClient, Response};
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The Project Role fields
+pub struct ProjectRoleDto {
+    /// The id of the project role (integer)
+    #[serde(rename = "ProjectRoleId")]
+    pub project_role_id: Option<u64>,
+
+    /// The name of the project role (string)
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    /// The description of the project role (string)
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+
+    /// Is the project role active (boolean)
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+
+    /// Can the members with this role administer the project (boolean)
+    #[serde(rename = "Admin")]
+    pub admin: Option<bool>,
+
+    /// Can the members with this role add documents (boolean)
+    #[serde(rename = "DocumentsAdd")]
+    pub documents_add: Option<bool>,
+
+    /// Can the members with this role edit documents (boolean)
+    #[serde(rename = "DocumentsEdit")]
+    pub documents_edit: Option<bool>,
+
+    /// Can the members with this role delete documents (boolean)
+    #[serde(rename = "DocumentsDelete")]
+    pub documents_delete: Option<bool>,
+
+    /// Can the members with this role add comments (boolean)
+    #[serde(rename = "DiscussionsAdd")]
+    pub discussions_add: Option<bool>,
+
+    /// Can the members with this role view the source code (boolean)
+    #[serde(rename = "SourceCodeView")]
+    pub source_code_view: Option<bool>,
+
+    /// Can the members with this role edit the source code associations (boolean)
+    #[serde(rename = "SourceCodeEdit")]
+    pub source_code_edit: Option<bool>,
+
+    /// Can the members with this role view the resources (boolean)
+    #[serde(rename = "ResourcesView")]
+    pub resources_view: Option<bool>,
+
+    /// The permissions of the role, per artifact type
+    #[serde(rename = "Permissions")]
+    pub permissions: Option<Vec<RolePermissionDto>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// A permission granted to a project role on an artifact type
+pub struct RolePermissionDto {
+    /// The id of the project role (integer)
+    #[serde(rename = "ProjectRoleId")]
+    pub project_role_id: u64,
+
+    /// The id of the artifact type (integer)
+    #[serde(rename = "ArtifactTypeId")]
+    pub artifact_type_id: u64,
+
+    /// The id of the permission (integer)
+    /// Relevant values: Create 1; Modify 2; Delete 3; View 4; Limited Modify 5
+    #[serde(rename = "PermissionId")]
+    pub permission_id: u64,
+}
+
+/// A permission on an artifact type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Permission {
+    Create,
+    Modify,
+    Delete,
+    View,
+    /// Modify only the artifacts the user created or owns
+    LimitedModify,
+}
+
+impl Permission {
+    pub fn id(&self) -> u64 {
+        match self {
+            Permission::Create => 1,
+            Permission::Modify => 2,
+            Permission::Delete => 3,
+            Permission::View => 4,
+            Permission::LimitedModify => 5,
+        }
+    }
+
+    pub fn from_id(id: u64) -> Option<Self> {
+        let permission = match id {
+            1 => Permission::Create,
+            2 => Permission::Modify,
+            3 => Permission::Delete,
+            4 => Permission::View,
+            5 => Permission::LimitedModify,
+            _ => return None,
+        };
+
+        Some(permission)
+    }
+}
+
+impl ProjectRoleDto {
+    /// Is the permission granted on the artifact type
+    pub fn can(&self, artifact_type: ArtifactType, permission: Permission) -> bool {
+        self.permissions.iter().flatten().any(|granted| {
+            granted.artifact_type_id == artifact_type.id()
+                && granted.permission_id == permission.id()
+        })
+    }
+
+    /// The permissions granted on the artifact type
+    pub fn permissions_on(&self, artifact_type: ArtifactType) -> Vec<Permission> {
+        self.permissions
+            .iter()
+            .flatten()
+            .filter(|granted| granted.artifact_type_id == artifact_type.id())
+            .filter_map(|granted| Permission::from_id(granted.permission_id))
+            .collect()
+    }
+}
+
+/// The Project Role Submodule
+pub struct ProjectRoleClient<'a> {
+    client: HttpClient,
+    base_url: &'a str,
+}
+
+impl<'a> ProjectRoleClient<'a> {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
+        ProjectRoleClient { client, base_url }
+    }
+
+    /// Retrieves all the project roles with their permissions
+    pub async fn list(&self) -> Response<Vec<ProjectRoleDto>> {
+        let project_roles = self
+            .client
+            .get(self.append_to_url("/project-roles"))
+            .send()
+            .await?
+            .json::<Vec<ProjectRoleDto>>()
+            .await?;
+
+        Ok(project_roles)
+    }
+
+    fn append_to_url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
@@ -1,7 +1,12 @@
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::Response;
+use crate::{
+    artifact::ArtifactType,
+    error::InvalidPayloadError,
+    resources::custom_property::{CustomListDto, CustomListValueDto, CustomPropertyDto},
+    transport::HttpClient,
+    Response,
+};
 
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct IncidentStatusDto {
@@ -15,28 +20,239 @@ pub struct IncidentStatusDto {
     pub open: Option<bool>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct IncidentPriorityDto {
+    #[serde(rename = "PriorityId")]
+    pub priority_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    /// The hex color code used to display the priority, e.g. "f47457"
+    #[serde(rename = "Color")]
+    pub color: Option<String>,
+    #[serde(rename = "Score")]
+    pub score: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct IncidentSeverityDto {
+    #[serde(rename = "SeverityId")]
+    pub severity_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    /// The hex color code used to display the severity, e.g. "f47457"
+    #[serde(rename = "Color")]
+    pub color: Option<String>,
+    #[serde(rename = "Score")]
+    pub score: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct IncidentTypeDto {
+    #[serde(rename = "IncidentTypeId")]
+    pub incident_type_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    /// Is this type an issue rather than a bug
+    #[serde(rename = "Issue")]
+    pub issue: Option<bool>,
+    /// Is this type a risk rather than a bug
+    #[serde(rename = "Risk")]
+    pub risk: Option<bool>,
+    /// The id of the workflow the incidents of this type follow
+    #[serde(rename = "WorkflowId")]
+    pub workflow_id: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TaskStatusDto {
+    #[serde(rename = "TaskStatusId")]
+    pub task_status_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    #[serde(rename = "Position")]
+    pub position: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TaskTypeDto {
+    #[serde(rename = "TaskTypeId")]
+    pub task_type_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    #[serde(rename = "Position")]
+    pub position: Option<u64>,
+    #[serde(rename = "IsDefault")]
+    pub is_default: Option<bool>,
+    /// The id of the workflow the tasks of this type follow
+    #[serde(rename = "WorkflowId")]
+    pub workflow_id: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TaskPriorityDto {
+    #[serde(rename = "PriorityId")]
+    pub priority_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    /// The hex color code used to display the priority, e.g. "f47457"
+    #[serde(rename = "Color")]
+    pub color: Option<String>,
+    #[serde(rename = "Score")]
+    pub score: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RequirementStatusDto {
+    #[serde(rename = "RequirementStatusId")]
+    pub requirement_status_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    #[serde(rename = "Position")]
+    pub position: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RequirementTypeDto {
+    /// The id of the requirement type, packages use -1
+    #[serde(rename = "RequirementTypeId")]
+    pub requirement_type_id: Option<i64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    #[serde(rename = "IsDefault")]
+    pub is_default: Option<bool>,
+    /// Do the requirements of this type have scenario steps
+    #[serde(rename = "IsSteps")]
+    pub is_steps: Option<bool>,
+    /// The id of the workflow the requirements of this type follow
+    #[serde(rename = "WorkflowId")]
+    pub workflow_id: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RequirementImportanceDto {
+    #[serde(rename = "ImportanceId")]
+    pub importance_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    /// The hex color code used to display the importance, e.g. "f47457"
+    #[serde(rename = "Color")]
+    pub color: Option<String>,
+    #[serde(rename = "Score")]
+    pub score: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RiskStatusDto {
+    #[serde(rename = "RiskStatusId")]
+    pub risk_status_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    #[serde(rename = "Open")]
+    pub open: Option<bool>,
+    #[serde(rename = "Position")]
+    pub position: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RiskTypeDto {
+    #[serde(rename = "RiskTypeId")]
+    pub risk_type_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    #[serde(rename = "IsDefault")]
+    pub is_default: Option<bool>,
+    /// The id of the workflow the risks of this type follow
+    #[serde(rename = "WorkflowId")]
+    pub workflow_id: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RiskProbabilityDto {
+    #[serde(rename = "RiskProbabilityId")]
+    pub risk_probability_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    /// The hex color code used to display the probability, e.g. "f47457"
+    #[serde(rename = "Color")]
+    pub color: Option<String>,
+    /// The score used to compute the risk exposure
+    #[serde(rename = "Score")]
+    pub score: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RiskImpactDto {
+    #[serde(rename = "RiskImpactId")]
+    pub risk_impact_id: Option<u64>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+    /// The hex color code used to display the impact, e.g. "f47457"
+    #[serde(rename = "Color")]
+    pub color: Option<String>,
+    /// The score used to compute the risk exposure
+    #[serde(rename = "Score")]
+    pub score: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct ProjectTemplateDto {
     #[serde(rename = "ProjectTemplateId")]
     pub project_template_id: Option<u64>,
     #[serde(rename = "Name")]
     pub name: Option<String>,
-    #[serde(rename = "Active")]
+    #[serde(rename = "Description")]
     pub description: Option<String>,
     #[serde(rename = "IsActive")]
     pub is_active: Option<bool>,
 }
 
 pub struct ProjectTemplateClient<'a> {
-    client: Client,
+    client: HttpClient,
     base_url: &'a str,
 }
 
 impl<'a> ProjectTemplateClient<'a> {
-    pub fn new(client: Client, base_url: &'a str) -> Self {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
         ProjectTemplateClient { client, base_url }
     }
 
+    /// Retrieves all the project templates the current user has access to
+    pub async fn list(&self) -> Response<Vec<ProjectTemplateDto>> {
+        self.fetch("/project-templates").await
+    }
+
+    /// Retrieves a single project template
+    pub async fn get(&self, project_template_id: u64) -> Response<ProjectTemplateDto> {
+        self.fetch(&format!("/project-templates/{}", project_template_id))
+            .await
+    }
+
     pub async fn incident_status_list(
         &self,
         project_template_id: u64,
@@ -55,6 +271,326 @@ impl<'a> ProjectTemplateClient<'a> {
         Ok(incident_statuses)
     }
 
+    pub async fn incident_priority_list(
+        &self,
+        project_template_id: u64,
+    ) -> Response<Vec<IncidentPriorityDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/incidents/priorities",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn incident_severity_list(
+        &self,
+        project_template_id: u64,
+    ) -> Response<Vec<IncidentSeverityDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/incidents/severities",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn incident_type_list(
+        &self,
+        project_template_id: u64,
+    ) -> Response<Vec<IncidentTypeDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/incidents/types",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn task_status_list(&self, project_template_id: u64) -> Response<Vec<TaskStatusDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/tasks/statuses",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn task_type_list(&self, project_template_id: u64) -> Response<Vec<TaskTypeDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/tasks/types",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn task_priority_list(
+        &self,
+        project_template_id: u64,
+    ) -> Response<Vec<TaskPriorityDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/tasks/priorities",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn requirement_status_list(
+        &self,
+        project_template_id: u64,
+    ) -> Response<Vec<RequirementStatusDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/requirements/statuses",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn requirement_type_list(
+        &self,
+        project_template_id: u64,
+    ) -> Response<Vec<RequirementTypeDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/requirements/types",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn requirement_importance_list(
+        &self,
+        project_template_id: u64,
+    ) -> Response<Vec<RequirementImportanceDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/requirements/importances",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn risk_status_list(&self, project_template_id: u64) -> Response<Vec<RiskStatusDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/risks/statuses",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn risk_type_list(&self, project_template_id: u64) -> Response<Vec<RiskTypeDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/risks/types",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn risk_probability_list(
+        &self,
+        project_template_id: u64,
+    ) -> Response<Vec<RiskProbabilityDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/risks/probabilities",
+            project_template_id
+        ))
+        .await
+    }
+
+    pub async fn risk_impact_list(&self, project_template_id: u64) -> Response<Vec<RiskImpactDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/risks/impacts",
+            project_template_id
+        ))
+        .await
+    }
+
+    /// Retrieves the custom property definitions of an artifact type
+    pub async fn custom_property_list(
+        &self,
+        project_template_id: u64,
+        artifact_type: ArtifactType,
+    ) -> Response<Vec<CustomPropertyDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/custom-properties/{}",
+            project_template_id,
+            artifact_type.name()
+        ))
+        .await
+    }
+
+    /// Retrieves the custom lists of a project template
+    pub async fn custom_list_list(&self, project_template_id: u64) -> Response<Vec<CustomListDto>> {
+        self.fetch(&format!(
+            "/project-templates/{}/custom-lists",
+            project_template_id
+        ))
+        .await
+    }
+
+    /// Retrieves a single custom list with its values
+    pub async fn custom_list_get(
+        &self,
+        project_template_id: u64,
+        custom_list_id: u64,
+    ) -> Response<CustomListDto> {
+        self.fetch(&format!(
+            "/project-templates/{}/custom-lists/{}",
+            project_template_id, custom_list_id
+        ))
+        .await
+    }
+
+    /// Creates a new custom list, with its values, in a project template
+    pub async fn custom_list_create(
+        &self,
+        project_template_id: u64,
+        custom_list: CustomListDto,
+    ) -> Response<CustomListDto> {
+        let json_custom_list = serde_json::to_string(&custom_list)?;
+        let custom_list = self
+            .client
+            .post(self.append_to_url(&format!(
+                "/project-templates/{}/custom-lists",
+                project_template_id
+            )))
+            .body(json_custom_list)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<CustomListDto>()
+            .await?;
+
+        Ok(custom_list)
+    }
+
+    /// Updates a custom list, including the name and active flag of its values
+    pub async fn custom_list_update(
+        &self,
+        project_template_id: u64,
+        custom_list: CustomListDto,
+    ) -> Response<()> {
+        let custom_list_id = InvalidPayloadError::require_id(
+            custom_list.custom_property_list_id,
+            "CustomPropertyListId",
+        )?;
+        let json_custom_list = serde_json::to_string(&custom_list)?;
+        self.client
+            .put(self.append_to_url(&format!(
+                "/project-templates/{}/custom-lists/{}",
+                project_template_id, custom_list_id
+            )))
+            .body(json_custom_list)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Deactivates a custom list, Spira does not allow deleting them
+    pub async fn custom_list_deactivate(
+        &self,
+        project_template_id: u64,
+        custom_list_id: u64,
+    ) -> Response<()> {
+        let mut custom_list = self
+            .custom_list_get(project_template_id, custom_list_id)
+            .await?;
+        custom_list.active = Some(false);
+
+        self.custom_list_update(project_template_id, custom_list)
+            .await
+    }
+
+    /// Adds a new value at the end of a custom list
+    pub async fn custom_list_value_create(
+        &self,
+        project_template_id: u64,
+        custom_list_id: u64,
+        value: CustomListValueDto,
+    ) -> Response<CustomListValueDto> {
+        let json_value = serde_json::to_string(&value)?;
+        let value = self
+            .client
+            .post(self.append_to_url(&format!(
+                "/project-templates/{}/custom-lists/{}/values",
+                project_template_id, custom_list_id
+            )))
+            .body(json_value)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<CustomListValueDto>()
+            .await?;
+
+        Ok(value)
+    }
+
+    /// Updates a value of a custom list, the values being saved together with their list
+    pub async fn custom_list_value_update(
+        &self,
+        project_template_id: u64,
+        custom_list_id: u64,
+        value: CustomListValueDto,
+    ) -> Response<()> {
+        let custom_property_value_id = InvalidPayloadError::require_id(
+            value.custom_property_value_id,
+            "CustomPropertyValueId",
+        )?;
+        let mut custom_list = self
+            .custom_list_get(project_template_id, custom_list_id)
+            .await?;
+        let existing = custom_list
+            .values
+            .iter_mut()
+            .flatten()
+            .find(|existing| existing.custom_property_value_id == Some(custom_property_value_id))
+            .ok_or_else(|| {
+                format!(
+                    "value {} is not part of the custom list {}",
+                    custom_property_value_id, custom_list_id
+                )
+            })?;
+        *existing = value;
+
+        self.custom_list_update(project_template_id, custom_list)
+            .await
+    }
+
+    /// Deactivates a value of a custom list, Spira does not allow deleting them
+    pub async fn custom_list_value_deactivate(
+        &self,
+        project_template_id: u64,
+        custom_list_id: u64,
+        custom_property_value_id: u64,
+    ) -> Response<()> {
+        let mut custom_list = self
+            .custom_list_get(project_template_id, custom_list_id)
+            .await?;
+        let existing = custom_list
+            .values
+            .iter_mut()
+            .flatten()
+            .find(|existing| existing.custom_property_value_id == Some(custom_property_value_id))
+            .ok_or_else(|| {
+                format!(
+                    "value {} is not part of the custom list {}",
+                    custom_property_value_id, custom_list_id
+                )
+            })?;
+        existing.active = Some(false);
+
+        self.custom_list_update(project_template_id, custom_list)
+            .await
+    }
+
+    async fn fetch<T: DeserializeOwned>(&self, path: &str) -> Response<T> {
+        let value = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<T>()
+            .await?;
+
+        Ok(value)
+    }
+
     fn append_to_url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
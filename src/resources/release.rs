@@ -1,30 +1,232 @@
-use reqwest::Client;
+use chrono::NaiveDate;
+use futures::Stream;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
-use crate::Response;
+use crate::{
+    date::parse_date,
+    error::{ConflictError, InvalidPayloadError},
+    hierarchy::{Indented, NodeId, Tree},
+    resources::custom_property::{ArtifactCustomPropertyDto, CustomProperties},
+    stream,
+    transport::{HttpClient, StatusCode},
+    Response,
+};
 
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Default)]
 /// The Release fields
 pub struct ReleaseDto {
-    /// The id of the incident (integer)
+    /// The id of the release (integer)
     #[serde(rename = "ReleaseId")]
     pub release_id: Option<u64>,
 
-    /// The id of the incident (integer)
+    /// The id of the user that created the release (integer) If no value is provided,
+    /// the authenticated user is used instead
+    #[serde(rename = "CreatorId")]
+    pub creator_id: Option<u64>,
+
+    /// The id of the user that the release is assigned-to (integer)
+    #[serde(rename = "OwnerId")]
+    pub owner_id: Option<u64>,
+
+    /// The indentation level of the artifact (string) The system uses a set of
+    /// three-letter segments to denote indent (e.g. AAA followed by AAB, etc.)
+    #[serde(rename = "IndentLevel")]
+    pub indent_level: Option<String>,
+
+    /// The name of the release (string - required for POST)
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    /// The description of the release (string)
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+
+    /// The version number of the release (string - required for POST)
+    #[serde(rename = "VersionNumber")]
+    pub version_number: String,
+
+    /// The date/time that the release was originally created
+    /// { "CreationDate": "2022-03-14T11:28:07.240Z" },
+    #[serde(rename = "CreationDate")]
+    pub creation_date: Option<String>,
+
+    /// The date/time that the release was last modified This field needs to match the values retrieved to ensure data-concurrency
+    #[serde(rename = "LastUpdateDate")]
+    pub last_update_date: Option<String>,
+
+    /// Is this a summary release or not (boolean)
+    #[serde(rename = "Summary")]
+    pub summary: Option<bool>,
+
+    /// Is this release active for the project (boolean)
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+
+    /// The date that the release starts on (date-time - required for POST)
+    #[serde(rename = "StartDate")]
+    pub start_date: Option<String>,
+
+    /// The date that the release ends on (date-time - required for POST)
+    #[serde(rename = "EndDate")]
+    pub end_date: Option<String>,
+
+    /// The id of the status of the release (integer)
+    /// Relevant values: Planned 1; In Progress 2; Completed 3; Closed 4; Deferred 5; Cancelled 6
+    #[serde(rename = "ReleaseStatusId")]
+    pub release_status_id: Option<u64>,
+
+    /// The id of the type of the release (integer)
+    /// Relevant values: Major Release 1; Minor Release 2; Sprint 3; Phase 4
+    #[serde(rename = "ReleaseTypeId")]
+    pub release_type_id: Option<u64>,
+
+    /// The number of people working on the release (decimal)
+    #[serde(rename = "ResourceCount")]
+    pub resource_count: Option<f32>,
+
+    /// The number of non-working days in the release (integer)
+    #[serde(rename = "DaysNonWorking")]
+    pub days_non_working: Option<u64>,
+
+    /// The planned effort (in minutes) of the release (integer - read only)
+    #[serde(rename = "PlannedEffort")]
+    pub planned_effort: Option<u64>,
+
+    /// The effort (in minutes) still available in the release (integer - read only)
+    #[serde(rename = "AvailableEffort")]
+    pub available_effort: Option<i64>,
+
+    /// The estimated effort (in minutes) of the tasks in the release (integer - read only)
+    #[serde(rename = "TaskEstimatedEffort")]
+    pub task_estimated_effort: Option<u64>,
+
+    /// The actual effort (in minutes) of the tasks in the release (integer - read only)
+    #[serde(rename = "TaskActualEffort")]
+    pub task_actual_effort: Option<u64>,
+
+    /// The number of tasks in the release (integer - read only)
+    #[serde(rename = "TaskCount")]
+    pub task_count: Option<u64>,
+
+    /// The full name of the release, made up of its name and version number (string - read only)
     #[serde(rename = "FullName")]
     pub full_name: Option<String>,
+
+    /// The id of the project that the artifact belongs to
+    #[serde(rename = "ProjectId")]
+    pub project_id: u64,
+
+    /// The datetime used to track optimistic concurrency to prevent edit conflicts
+    #[serde(rename = "ConcurrencyDate")]
+    pub concurrency_date: Option<String>,
+
+    /// Does this artifact have any attachments?
+    #[serde(rename = "IsAttachments")]
+    pub is_attachements: Option<bool>,
+
+    /// The values of the custom properties of the artifact
+    #[serde(rename = "CustomProperties")]
+    pub custom_properties: Option<Vec<ArtifactCustomPropertyDto>>,
 }
 
-/// The Requirement Artifact Submodule
+impl CustomProperties for ReleaseDto {
+    fn custom_properties(&self) -> Option<&Vec<ArtifactCustomPropertyDto>> {
+        self.custom_properties.as_ref()
+    }
+
+    fn custom_properties_mut(&mut self) -> &mut Option<Vec<ArtifactCustomPropertyDto>> {
+        &mut self.custom_properties
+    }
+}
+
+impl ReleaseDto {
+    /// Is this release a sprint or a phase, i.e. an iteration of a parent release
+    pub fn is_iteration(&self) -> bool {
+        matches!(self.release_type_id, Some(3) | Some(4))
+    }
+}
+
+impl Indented for ReleaseDto {
+    fn indent_level(&self) -> Option<&str> {
+        self.indent_level.as_deref()
+    }
+}
+
+/// The release hierarchy of a project: releases with their child sprints and phases
+pub type ReleaseTree = Tree<ReleaseDto>;
+
+impl Tree<ReleaseDto> {
+    /// Finds the active sprint whose start and end dates include the given date
+    pub fn current_sprint(&self, on: NaiveDate) -> Option<NodeId> {
+        self.find(|release| {
+            let dates = (
+                release.start_date.as_deref().and_then(parse_date),
+                release.end_date.as_deref().and_then(parse_date),
+            );
+
+            release.release_type_id == Some(3)
+                && release.active != Some(false)
+                && matches!(dates, (Some(start), Some(end)) if start <= on && on <= end)
+        })
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// A test case mapped to a release
+pub struct ReleaseTestCaseDto {
+    /// The id of the test case (integer)
+    #[serde(rename = "TestCaseId")]
+    pub test_case_id: Option<u64>,
+
+    /// The name of the test case (string)
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    /// The description of the test case (string)
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+
+    /// The id of the status of the test case (integer)
+    #[serde(rename = "TestCaseStatusId")]
+    pub test_case_status_id: Option<u64>,
+
+    /// The id of the last execution status of the test case (integer)
+    /// Relevant values: Failed 1; Passed 2; Not Run 3; N/A 4; Blocked 5; Caution 6
+    #[serde(rename = "ExecutionStatusId")]
+    pub execution_status_id: Option<u64>,
+
+    /// The id of the user that the test case is assigned-to (integer)
+    #[serde(rename = "OwnerId")]
+    pub owner_id: Option<u64>,
+
+    /// The id of the project that the artifact belongs to
+    #[serde(rename = "ProjectId")]
+    pub project_id: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The mapping between a release and a test case
+pub struct ReleaseTestCaseMappingDto {
+    /// The id of the release (integer)
+    #[serde(rename = "ReleaseId")]
+    pub release_id: u64,
+
+    /// The id of the test case (integer)
+    #[serde(rename = "TestCaseId")]
+    pub test_case_id: u64,
+}
+
+/// The Release Artifact Submodule
 pub struct ReleaseClient<'a> {
-    client: Client,
+    client: HttpClient,
     base_url: &'a str,
 }
 
 impl<'a> ReleaseClient<'a> {
-    pub fn new(client: Client, base_url: &'a str) -> Self {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
         ReleaseClient { client, base_url }
     }
 
@@ -43,6 +245,206 @@ impl<'a> ReleaseClient<'a> {
         Ok(releases)
     }
 
+    /// Streams the releases of the project, deserializing each one as soon as it is received
+    pub fn list_stream(&self, project_id: u64) -> impl Stream<Item = Response<ReleaseDto>> {
+        let path = &format!("/projects/{}/releases", project_id);
+
+        stream::json_array(self.client.get(self.append_to_url(path)))
+    }
+
+    /// Retrieves all the releases of the project arranged in their hierarchy
+    pub async fn tree(&self, project_id: u64) -> Response<ReleaseTree> {
+        let releases = self.list(project_id).await?;
+
+        Ok(Tree::from_flat(releases))
+    }
+
+    /// Retrieves a single release in the system
+    pub async fn get(&self, project_id: u64, release_id: u64) -> Response<ReleaseDto> {
+        let path = &format!("/projects/{}/releases/{}", project_id, release_id);
+
+        let release = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<ReleaseDto>()
+            .await?;
+
+        Ok(release)
+    }
+
+    /// Creates a new release at the end of the project's release list
+    pub async fn create(&self, project_id: u64, release: ReleaseDto) -> Response<ReleaseDto> {
+        let json_release = serde_json::to_string(&release)?;
+        let release = self
+            .client
+            .post(self.append_to_url(&format!("/projects/{}/releases", project_id)))
+            .body(json_release)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ReleaseDto>()
+            .await?;
+
+        Ok(release)
+    }
+
+    /// Creates a new release or iteration as a child of the specified parent release
+    pub async fn create_child(
+        &self,
+        project_id: u64,
+        parent_release_id: u64,
+        release: ReleaseDto,
+    ) -> Response<ReleaseDto> {
+        let json_release = serde_json::to_string(&release)?;
+        let release = self
+            .client
+            .post(self.append_to_url(&format!(
+                "/projects/{}/releases/{}",
+                project_id, parent_release_id
+            )))
+            .body(json_release)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ReleaseDto>()
+            .await?;
+
+        Ok(release)
+    }
+
+    /// Updates a release in the system. When the release was modified since it was retrieved,
+    /// fails with a ConflictError holding the current copy of the release
+    pub async fn update(&self, project_id: u64, release: ReleaseDto) -> Response<()> {
+        let release_id = InvalidPayloadError::require_id(release.release_id, "ReleaseId")?;
+        let json_release = serde_json::to_string(&release)?;
+        let response = self
+            .client
+            .put(self.append_to_url(&format!("/projects/{}/releases", project_id)))
+            .body(json_release)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::CONFLICT {
+            let current = self.get(project_id, release_id).await?;
+            return Err(Box::new(ConflictError { current }));
+        }
+        response.error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Retrieves a release, applies the changes and updates it, retrying on conflicts
+    /// with the current copy of the release up to max_retries times
+    pub async fn update_with_retry<F>(
+        &self,
+        project_id: u64,
+        release_id: u64,
+        max_retries: u32,
+        mut apply: F,
+    ) -> Response<()>
+    where
+        F: FnMut(&mut ReleaseDto),
+    {
+        let mut release = self.get(project_id, release_id).await?;
+        let mut retries = 0;
+
+        loop {
+            apply(&mut release);
+
+            match self.update(project_id, release).await {
+                Err(error) if retries < max_retries => {
+                    release = error.downcast::<ConflictError<ReleaseDto>>()?.current;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Deletes a release in the system
+    pub async fn delete(&self, project_id: u64, release_id: u64) -> Response<()> {
+        self.client
+            .delete(
+                self.append_to_url(&format!("/projects/{}/releases/{}", project_id, release_id)),
+            )
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Retrieves the test cases mapped to a release
+    pub async fn test_case_list(
+        &self,
+        project_id: u64,
+        release_id: u64,
+    ) -> Response<Vec<ReleaseTestCaseDto>> {
+        let path = &format!(
+            "/projects/{}/releases/{}/test-cases",
+            project_id, release_id
+        );
+
+        let test_cases = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<ReleaseTestCaseDto>>()
+            .await?;
+
+        Ok(test_cases)
+    }
+
+    /// Maps a list of test cases to a release
+    pub async fn test_case_add(
+        &self,
+        project_id: u64,
+        release_id: u64,
+        test_case_ids: &[u64],
+    ) -> Response<()> {
+        let mappings = test_case_ids
+            .iter()
+            .map(|test_case_id| ReleaseTestCaseMappingDto {
+                release_id,
+                test_case_id: *test_case_id,
+            })
+            .collect::<Vec<_>>();
+        let json_mappings = serde_json::to_string(&mappings)?;
+        self.client
+            .post(self.append_to_url(&format!(
+                "/projects/{}/releases/{}/test-cases",
+                project_id, release_id
+            )))
+            .body(json_mappings)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Removes the mapping between a test case and a release
+    pub async fn test_case_remove(
+        &self,
+        project_id: u64,
+        release_id: u64,
+        test_case_id: u64,
+    ) -> Response<()> {
+        self.client
+            .delete(self.append_to_url(&format!(
+                "/projects/{}/releases/{}/test-cases/{}",
+                project_id, release_id, test_case_id
+            )))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
     fn append_to_url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
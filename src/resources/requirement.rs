@@ -1,8 +1,26 @@
-use reqwest::Client;
+use std::error::Error;
+
+use futures::Stream;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
-use crate::Response;
+use crate::{
+    batch::{self, BatchOptions, BatchResults},
+    error::{ConflictError, InvalidPayloadError},
+    hierarchy::{Indented, Tree},
+    resources::custom_property::{
+        ArtifactCustomPropertyDto, CustomProperties, CustomPropertyError, CustomPropertyValue,
+    },
+    stream,
+    transport::{HttpClient, StatusCode},
+    Response,
+};
+
+/// The number of requirements fetched per request when building the requirement tree
+const TREE_PAGE_SIZE: u64 = 500;
+
+/// The number of times a patch is retried on concurrency conflicts
+const PATCH_MAX_RETRIES: u32 = 3;
 
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -28,7 +46,7 @@ pub struct RequirementDto {
     /// Feature 2; Use Case 3; User Story 4; Quality 5; Design Element 6 Null
     /// can be passed when created if using the default type
     #[serde(rename = "RequirementTypeId")]
-    pub requirement_type_id: Option<u64>,
+    pub requirement_type_id: Option<i64>,
 
     /// The id of the user that wrote the requirement (integer)
     /// If no value is provided, the authenticated user is used instead
@@ -41,7 +59,7 @@ pub struct RequirementDto {
 
     /// The id of the importance of the requirement (integer)
     /// Relevant values: 1 - Critical 1; 2 - High 2; 3 - Medium 3; 4 - Low 4
-    #[serde(rename = "ReleaseId")]
+    #[serde(rename = "ImportanceId")]
     pub importance_id: Option<u64>,
 
     /// The id of the release the requirement is scheduled to implemented in (integer)
@@ -80,7 +98,7 @@ pub struct RequirementDto {
 
     /// The list of scenarios steps (array - only available for Use Case requirement types)
     #[serde(rename = "Steps")]
-    pub steps: Option<Vec<String>>,
+    pub steps: Option<Vec<RequirementStepDto>>,
 
     /// The start date of the requirement for planning purposes
     #[serde(rename = "StartDate")]
@@ -117,16 +135,137 @@ pub struct RequirementDto {
     /// Does this artifact have any attachments?
     #[serde(rename = "IsAttachments")]
     pub is_attachements: Option<bool>,
+
+    /// The values of the custom properties of the artifact
+    #[serde(rename = "CustomProperties")]
+    pub custom_properties: Option<Vec<ArtifactCustomPropertyDto>>,
+}
+
+impl CustomProperties for RequirementDto {
+    fn custom_properties(&self) -> Option<&Vec<ArtifactCustomPropertyDto>> {
+        self.custom_properties.as_ref()
+    }
+
+    fn custom_properties_mut(&mut self) -> &mut Option<Vec<ArtifactCustomPropertyDto>> {
+        &mut self.custom_properties
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The Requirement Step fields, the scenario steps of a Use Case requirement
+pub struct RequirementStepDto {
+    /// The id of the requirement step (integer)
+    #[serde(rename = "RequirementStepId")]
+    pub requirement_step_id: Option<u64>,
+
+    /// The id of the requirement the step belongs to (integer)
+    #[serde(rename = "RequirementId")]
+    pub requirement_id: Option<u64>,
+
+    /// The position of the step in the scenario, starting at 1 (integer - read only)
+    #[serde(rename = "Position")]
+    pub position: Option<u64>,
+
+    /// The description of the step (string - required for POST)
+    #[serde(rename = "Description")]
+    pub description: String,
+
+    /// The date/time that the step was originally created
+    /// { "CreationDate": "2022-03-14T11:28:07.240Z" },
+    #[serde(rename = "CreationDate")]
+    pub creation_date: Option<String>,
+
+    /// The date/time that the step was last modified This field needs to match the values retrieved to ensure data-concurrency
+    #[serde(rename = "LastUpdateDate")]
+    pub last_update_date: Option<String>,
+
+    /// The id of the project that the artifact belongs to
+    #[serde(rename = "ProjectId")]
+    pub project_id: Option<u64>,
+
+    /// The datetime used to track optimistic concurrency to prevent edit conflicts
+    #[serde(rename = "ConcurrencyDate")]
+    pub concurrency_date: Option<String>,
+}
+
+impl RequirementDto {
+    /// Is this requirement a package, i.e. a grouping of other requirements,
+    /// marked by either the package type or the summary flag
+    pub fn is_package(&self) -> bool {
+        self.requirement_type_id == Some(-1) || self.summary == Some(true)
+    }
+}
+
+impl Indented for RequirementDto {
+    fn indent_level(&self) -> Option<&str> {
+        self.indent_level.as_deref()
+    }
+}
+
+/// The requirement hierarchy of a project: packages with their child requirements
+pub type RequirementTree = Tree<RequirementDto>;
+
+/// A change to a single field of a requirement, applied by RequirementClient::patch
+#[derive(Debug, Clone)]
+pub enum RequirementChange {
+    /// The name of the requirement
+    Name(String),
+    /// The description of the requirement
+    Description(Option<String>),
+    /// The id of the status of the requirement
+    Status(u64),
+    /// The id of the type of the requirement
+    Type(i64),
+    /// The id of the importance of the requirement
+    Importance(Option<u64>),
+    /// The id of the user the requirement is assigned to
+    Owner(Option<u64>),
+    /// The id of the release the requirement is scheduled for
+    Release(Option<u64>),
+    /// The id of the component of the requirement
+    Component(Option<u64>),
+    /// The estimate of the requirement in story points
+    EstimatePoints(Option<f32>),
+    /// Sets, or clears when None, the value of a custom property by its number
+    CustomProperty {
+        property_number: u64,
+        value: Option<CustomPropertyValue>,
+    },
+}
+
+impl RequirementChange {
+    /// Applies the change to the requirement, failing on the custom property values
+    /// that can't be sent
+    pub fn apply(&self, requirement: &mut RequirementDto) -> Result<(), CustomPropertyError> {
+        match self {
+            RequirementChange::Name(value) => requirement.name = value.clone(),
+            RequirementChange::Description(value) => requirement.description = value.clone(),
+            RequirementChange::Status(value) => requirement.status_id = Some(*value),
+            RequirementChange::Type(value) => requirement.requirement_type_id = Some(*value),
+            RequirementChange::Importance(value) => requirement.importance_id = *value,
+            RequirementChange::Owner(value) => requirement.owner_id = *value,
+            RequirementChange::Release(value) => requirement.release_id = *value,
+            RequirementChange::Component(value) => requirement.component_id = *value,
+            RequirementChange::EstimatePoints(value) => requirement.estimate_points = *value,
+            RequirementChange::CustomProperty {
+                property_number,
+                value,
+            } => return requirement.set_custom_property_value(*property_number, value.clone()),
+        }
+
+        Ok(())
+    }
 }
 
 /// The Requirement Artifact Submodule
 pub struct RequirementClient<'a> {
-    client: Client,
+    client: HttpClient,
     base_url: &'a str,
 }
 
 impl<'a> RequirementClient<'a> {
-    pub fn new(client: Client, base_url: &'a str) -> Self {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
         RequirementClient { client, base_url }
     }
 
@@ -143,6 +282,89 @@ impl<'a> RequirementClient<'a> {
         Ok(requirements)
     }
 
+    /// Streams the requirements owned by the currently authenticated user, deserializing
+    /// each one as soon as it is received
+    pub fn list_my_stream(&self) -> impl Stream<Item = Response<RequirementDto>> {
+        stream::json_array(self.client.get(self.append_to_url("/requirements")))
+    }
+
+    /// Retrieves a page of the requirements in the project, in hierarchy order.
+    /// Note: the starting row is 1 based
+    pub async fn list(
+        &self,
+        project_id: u64,
+        starting_row: u64,
+        number_of_rows: u64,
+    ) -> Response<Vec<RequirementDto>> {
+        let path = &format!(
+            "/projects/{}/requirements?starting_row={}&number_of_rows={}",
+            project_id, starting_row, number_of_rows
+        );
+
+        let requirements = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<RequirementDto>>()
+            .await?;
+
+        Ok(requirements)
+    }
+
+    /// Streams the requirements in the project, in hierarchy order, fetching them
+    /// page by page
+    pub fn list_stream(
+        &self,
+        project_id: u64,
+        page_size: u64,
+    ) -> impl Stream<Item = Response<RequirementDto>> + '_ {
+        stream::paged(page_size, move |starting_row, number_of_rows| {
+            self.list(project_id, starting_row, number_of_rows)
+        })
+    }
+
+    /// Retrieves all the requirements of the project arranged in their hierarchy
+    pub async fn tree(&self, project_id: u64) -> Response<RequirementTree> {
+        let mut requirements = Vec::new();
+
+        loop {
+            let page = self
+                .list(project_id, requirements.len() as u64 + 1, TREE_PAGE_SIZE)
+                .await?;
+            let is_last_page = (page.len() as u64) < TREE_PAGE_SIZE;
+            requirements.extend(page);
+
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(Tree::from_flat(requirements))
+    }
+
+    /// Retrieves the direct children of a requirement
+    pub async fn children(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+    ) -> Response<Vec<RequirementDto>> {
+        let path = &format!(
+            "/projects/{}/requirements/{}/children",
+            project_id, requirement_id
+        );
+
+        let requirements = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<RequirementDto>>()
+            .await?;
+
+        Ok(requirements)
+    }
+
     /// Retrieves a single requirement in the system
     pub async fn get(&self, project_id: u64, requirement_id: u64) -> Response<RequirementDto> {
         let path = &format!("/projects/{}/requirements/{}", project_id, requirement_id);
@@ -172,28 +394,211 @@ impl<'a> RequirementClient<'a> {
             .body(json_requirement)
             .send()
             .await?
+            .error_for_status()?
             .json::<RequirementDto>()
             .await?;
 
         Ok(requirement)
     }
 
-    /// Updates a requirement in the system
-    pub async fn update(&self, project_id: u64, requirement: RequirementDto) -> Response<()> {
+    /// Creates a new requirement as the last child of the specified parent requirement
+    pub async fn create_child(
+        &self,
+        project_id: u64,
+        parent_requirement_id: u64,
+        requirement: RequirementDto,
+    ) -> Response<RequirementDto> {
         let json_requirement = serde_json::to_string(&requirement)?;
+        let requirement = self
+            .client
+            .post(self.append_to_url(&format!(
+                "/projects/{}/requirements/parent/{}",
+                project_id, parent_requirement_id
+            )))
+            .body(json_requirement)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<RequirementDto>()
+            .await?;
+
+        Ok(requirement)
+    }
+
+    /// Indents a requirement, making it a child of the requirement above it
+    pub async fn indent(&self, project_id: u64, requirement_id: u64) -> Response<()> {
+        self.client
+            .post(self.append_to_url(&format!(
+                "/projects/{}/requirements/{}/indent",
+                project_id, requirement_id
+            )))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Outdents a requirement, making it a sibling of its current parent
+    pub async fn outdent(&self, project_id: u64, requirement_id: u64) -> Response<()> {
         self.client
+            .post(self.append_to_url(&format!(
+                "/projects/{}/requirements/{}/outdent",
+                project_id, requirement_id
+            )))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Moves a requirement, with its children, just before the destination requirement.
+    /// When no destination is provided the requirement is moved to the end of the list
+    pub async fn move_to(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+        destination_requirement_id: Option<u64>,
+    ) -> Response<()> {
+        let mut path = format!(
+            "/projects/{}/requirements/{}/move",
+            project_id, requirement_id
+        );
+        if let Some(destination_requirement_id) = destination_requirement_id {
+            path.push_str(&format!(
+                "?destination_requirement_id={}",
+                destination_requirement_id
+            ));
+        }
+
+        self.client
+            .post(self.append_to_url(&path))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Moves a requirement, with its children, to become the first child of the parent requirement.
+    /// When the parent has no children this takes three requests (move, swap and indent) and is
+    /// not atomic: a failure halfway leaves the requirements reordered, the error naming the
+    /// step that failed
+    pub async fn move_under(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+        parent_requirement_id: u64,
+    ) -> Response<()> {
+        let children = self.children(project_id, parent_requirement_id).await?;
+
+        match children.first().and_then(|child| child.requirement_id) {
+            Some(first_child_id) => {
+                self.move_to(project_id, requirement_id, Some(first_child_id))
+                    .await?
+            }
+            None => {
+                // Spira can only move before a requirement, so place the requirement just
+                // above the parent, swap them and indent it under the parent
+                self.move_to(project_id, requirement_id, Some(parent_requirement_id))
+                    .await
+                    .map_err(|error| move_under_error("moving it above the parent", error))?;
+                self.move_to(project_id, parent_requirement_id, Some(requirement_id))
+                    .await
+                    .map_err(|error| move_under_error("moving the parent above it", error))?;
+                self.indent(project_id, requirement_id)
+                    .await
+                    .map_err(|error| move_under_error("indenting it under the parent", error))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Updates a requirement in the system. When the requirement was modified since it was retrieved,
+    /// fails with a ConflictError holding the current copy of the requirement
+    pub async fn update(&self, project_id: u64, requirement: RequirementDto) -> Response<()> {
+        let requirement_id =
+            InvalidPayloadError::require_id(requirement.requirement_id, "RequirementId")?;
+        let json_requirement = serde_json::to_string(&requirement)?;
+        let response = self
+            .client
             .put(self.append_to_url(&format!(
                 "/projects/{}/requirements/{}",
-                project_id,
-                requirement.requirement_id.unwrap()
+                project_id, requirement_id
             )))
             .body(json_requirement)
             .send()
             .await?;
 
+        if response.status() == StatusCode::CONFLICT {
+            let current = self.get(project_id, requirement_id).await?;
+            return Err(Box::new(ConflictError { current }));
+        }
+        response.error_for_status()?;
+
         Ok(())
     }
 
+    /// Retrieves a requirement, applies the changes and updates it, retrying on conflicts
+    /// with the current copy of the requirement up to max_retries times
+    pub async fn update_with_retry<F>(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+        max_retries: u32,
+        mut apply: F,
+    ) -> Response<()>
+    where
+        F: FnMut(&mut RequirementDto),
+    {
+        let mut requirement = self.get(project_id, requirement_id).await?;
+        let mut retries = 0;
+
+        loop {
+            apply(&mut requirement);
+
+            match self.update(project_id, requirement).await {
+                Err(error) if retries < max_retries => {
+                    requirement = error.downcast::<ConflictError<RequirementDto>>()?.current;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Applies the changes to the latest version of the requirement on the server, leaving
+    /// its other fields untouched. Concurrency conflicts are retried on the fresh copy
+    pub async fn patch(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+        changes: &[RequirementChange],
+    ) -> Response<()> {
+        // the changes only fail on the custom property values that can't be sent,
+        // whatever the requirement they are applied to
+        let mut checked = RequirementDto::default();
+        for change in changes {
+            change.apply(&mut checked)?;
+        }
+
+        self.update_with_retry(
+            project_id,
+            requirement_id,
+            PATCH_MAX_RETRIES,
+            |requirement| {
+                for change in changes {
+                    change
+                        .apply(requirement)
+                        .expect("the changes were checked before the update");
+                }
+            },
+        )
+        .await
+    }
+
     /// Deletes a requirement in the system
     pub async fn delete(&self, project_id: u64, requirement_id: u64) -> Response<()> {
         self.client
@@ -202,12 +607,248 @@ impl<'a> RequirementClient<'a> {
                 project_id, requirement_id
             )))
             .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Retrieves the scenario steps of a Use Case requirement, ordered by position
+    pub async fn step_list(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+    ) -> Response<Vec<RequirementStepDto>> {
+        let path = &format!(
+            "/projects/{}/requirements/{}/steps",
+            project_id, requirement_id
+        );
+
+        let steps = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<RequirementStepDto>>()
             .await?;
 
+        Ok(steps)
+    }
+
+    /// Retrieves a single scenario step of a requirement
+    pub async fn step_get(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+        requirement_step_id: u64,
+    ) -> Response<RequirementStepDto> {
+        let path = &format!(
+            "/projects/{}/requirements/{}/steps/{}",
+            project_id, requirement_id, requirement_step_id
+        );
+
+        let step = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<RequirementStepDto>()
+            .await?;
+
+        Ok(step)
+    }
+
+    /// Adds a scenario step to a requirement, inserted before the existing step when one
+    /// is provided, otherwise at the end of the scenario
+    pub async fn step_create(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+        step: RequirementStepDto,
+        existing_requirement_step_id: Option<u64>,
+    ) -> Response<RequirementStepDto> {
+        let mut path = format!(
+            "/projects/{}/requirements/{}/steps",
+            project_id, requirement_id
+        );
+        if let Some(existing_requirement_step_id) = existing_requirement_step_id {
+            path.push_str(&format!(
+                "?existing_requirement_step_id={}",
+                existing_requirement_step_id
+            ));
+        }
+
+        let json_step = serde_json::to_string(&step)?;
+        let step = self
+            .client
+            .post(self.append_to_url(&path))
+            .body(json_step)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<RequirementStepDto>()
+            .await?;
+
+        Ok(step)
+    }
+
+    /// Updates a scenario step of a requirement. When the step was modified since it was retrieved,
+    /// fails with a ConflictError holding the current copy of the step
+    pub async fn step_update(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+        step: RequirementStepDto,
+    ) -> Response<()> {
+        let requirement_step_id =
+            InvalidPayloadError::require_id(step.requirement_step_id, "RequirementStepId")?;
+        let json_step = serde_json::to_string(&step)?;
+        let response = self
+            .client
+            .put(self.append_to_url(&format!(
+                "/projects/{}/requirements/{}/steps",
+                project_id, requirement_id
+            )))
+            .body(json_step)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::CONFLICT {
+            let current = self
+                .step_get(project_id, requirement_id, requirement_step_id)
+                .await?;
+            return Err(Box::new(ConflictError { current }));
+        }
+        response.error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Moves a scenario step just before the destination step.
+    /// When no destination is provided the step is moved to the end of the scenario
+    pub async fn step_move(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+        requirement_step_id: u64,
+        destination_requirement_step_id: Option<u64>,
+    ) -> Response<()> {
+        let mut path = format!(
+            "/projects/{}/requirements/{}/steps/{}/move",
+            project_id, requirement_id, requirement_step_id
+        );
+        if let Some(destination_requirement_step_id) = destination_requirement_step_id {
+            path.push_str(&format!(
+                "?destination_step_id={}",
+                destination_requirement_step_id
+            ));
+        }
+
+        self.client
+            .post(self.append_to_url(&path))
+            .send()
+            .await?
+            .error_for_status()?;
+
         Ok(())
     }
 
+    /// Deletes a scenario step of a requirement
+    pub async fn step_delete(
+        &self,
+        project_id: u64,
+        requirement_id: u64,
+        requirement_step_id: u64,
+    ) -> Response<()> {
+        self.client
+            .delete(self.append_to_url(&format!(
+                "/projects/{}/requirements/{}/steps/{}",
+                project_id, requirement_id, requirement_step_id
+            )))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Creates the requirements of the batch, the results holding the ids assigned by the server
+    pub async fn create_batch(
+        &self,
+        project_id: u64,
+        requirements: Vec<RequirementDto>,
+        options: &BatchOptions,
+    ) -> BatchResults {
+        batch::run(requirements, options, |requirement| async move {
+            batch::validate(&requirement, missing_fields(&requirement, false))?;
+            if options.dry_run {
+                return Ok(None);
+            }
+
+            Ok(self.create(project_id, requirement).await?.requirement_id)
+        })
+        .await
+    }
+
+    /// Updates the requirements of the batch
+    pub async fn update_batch(
+        &self,
+        project_id: u64,
+        requirements: Vec<RequirementDto>,
+        options: &BatchOptions,
+    ) -> BatchResults {
+        batch::run(requirements, options, |requirement| async move {
+            batch::validate(&requirement, missing_fields(&requirement, true))?;
+            let requirement_id = requirement.requirement_id;
+            if !options.dry_run {
+                self.update(project_id, requirement).await?;
+            }
+
+            Ok(requirement_id)
+        })
+        .await
+    }
+
+    /// Deletes the requirements of the batch
+    pub async fn delete_batch(
+        &self,
+        project_id: u64,
+        requirement_ids: Vec<u64>,
+        options: &BatchOptions,
+    ) -> BatchResults {
+        batch::run(requirement_ids, options, |requirement_id| async move {
+            if !options.dry_run {
+                self.delete(project_id, requirement_id).await?;
+            }
+
+            Ok(Some(requirement_id))
+        })
+        .await
+    }
+
     fn append_to_url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
 }
+
+/// Names the step of RequirementClient::move_under that failed
+fn move_under_error(step: &str, error: Box<dyn Error>) -> Box<dyn Error> {
+    format!(
+        "moving the requirement under its parent failed when {}, the requirements may be left reordered: {}",
+        step, error
+    )
+    .into()
+}
+
+/// The fields Spira requires on a requirement, the id being required on updates
+fn missing_fields(requirement: &RequirementDto, update: bool) -> Vec<&'static str> {
+    let mut missing_fields = Vec::new();
+    if requirement.name.is_empty() {
+        missing_fields.push("Name");
+    }
+    if update && requirement.requirement_id.is_none() {
+        missing_fields.push("RequirementId");
+    }
+
+    missing_fields
+}
@@ -0,0 +1,398 @@
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::{
+    error::{ConflictError, InvalidPayloadError},
+    resources::custom_property::{ArtifactCustomPropertyDto, CustomProperties},
+    stream,
+    transport::{HttpClient, StatusCode},
+    Response,
+};
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The Risk fields
+pub struct RiskDto {
+    /// The id of the risk (integer)
+    #[serde(rename = "RiskId")]
+    pub risk_id: Option<u64>,
+
+    /// The id of the status of the risk (integer) If no value is provided,
+    /// the default status for the workflow is used
+    #[serde(rename = "RiskStatusId")]
+    pub risk_status_id: Option<u64>,
+
+    /// The id of the type of the risk (integer) If no value is provided,
+    /// the default type for the project is used
+    #[serde(rename = "RiskTypeId")]
+    pub risk_type_id: Option<u64>,
+
+    /// The id of the probability of the risk (integer)
+    #[serde(rename = "RiskProbabilityId")]
+    pub risk_probability_id: Option<u64>,
+
+    /// The id of the impact of the risk (integer)
+    #[serde(rename = "RiskImpactId")]
+    pub risk_impact_id: Option<u64>,
+
+    /// The exposure of the risk, the product of its probability and impact scores (integer - read only)
+    #[serde(rename = "RiskExposure")]
+    pub risk_exposure: Option<u64>,
+
+    /// The id of the user who identified the risk (integer) If a value is not provided,
+    /// the authenticated user is used
+    #[serde(rename = "CreatorId")]
+    pub creator_id: Option<u64>,
+
+    /// The id of the user that the risk is assigned-to (integer)
+    #[serde(rename = "OwnerId")]
+    pub owner_id: Option<u64>,
+
+    /// The id of the release/iteration that the risk relates to (integer)
+    #[serde(rename = "ReleaseId")]
+    pub release_id: Option<u64>,
+
+    /// The id of the component that the risk relates to (integer)
+    #[serde(rename = "ComponentId")]
+    pub component_id: Option<u64>,
+
+    /// The name of the risk (string)
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    /// The description of the risk (string)
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+
+    /// The date that the risk is due to be reviewed (date-time)
+    #[serde(rename = "ReviewDate")]
+    pub review_date: Option<String>,
+
+    /// The date that the risk was closed (date-time)
+    #[serde(rename = "ClosedDate")]
+    pub closed_date: Option<String>,
+
+    /// The date/time that the risk was originally created If no value is provided,
+    /// the current date/time on the server is used (date-time)
+    #[serde(rename = "CreationDate")]
+    pub creation_date: Option<String>,
+
+    /// The date/time that the risk was last modified This field needs to match the values retrieved to ensure data-concurrency
+    #[serde(rename = "LastUpdateDate")]
+    pub last_update_date: Option<String>,
+
+    /// The id of the project that the artifact belongs to
+    #[serde(rename = "ProjectId")]
+    pub project_id: u64,
+
+    /// The datetime used to track optimistic concurrency to prevent edit conflicts
+    #[serde(rename = "ConcurrencyDate")]
+    pub concurrency_date: Option<String>,
+
+    /// Does this artifact have any attachments?
+    #[serde(rename = "IsAttachments")]
+    pub is_attachements: Option<bool>,
+
+    /// The values of the custom properties of the artifact
+    #[serde(rename = "CustomProperties")]
+    pub custom_properties: Option<Vec<ArtifactCustomPropertyDto>>,
+}
+
+impl CustomProperties for RiskDto {
+    fn custom_properties(&self) -> Option<&Vec<ArtifactCustomPropertyDto>> {
+        self.custom_properties.as_ref()
+    }
+
+    fn custom_properties_mut(&mut self) -> &mut Option<Vec<ArtifactCustomPropertyDto>> {
+        &mut self.custom_properties
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The Risk Mitigation fields, the actions planned to reduce a risk
+pub struct RiskMitigationDto {
+    /// The id of the mitigation (integer)
+    #[serde(rename = "RiskMitigationId")]
+    pub risk_mitigation_id: Option<u64>,
+
+    /// The id of the risk the mitigation belongs to (integer)
+    #[serde(rename = "RiskId")]
+    pub risk_id: Option<u64>,
+
+    /// The position of the mitigation in the list, starting at 1 (integer - read only)
+    #[serde(rename = "Position")]
+    pub position: Option<u64>,
+
+    /// The description of the mitigation (string - required for POST)
+    #[serde(rename = "Description")]
+    pub description: String,
+
+    /// The date that the mitigation is due to be reviewed (date-time)
+    #[serde(rename = "ReviewDate")]
+    pub review_date: Option<String>,
+
+    /// The date/time that the mitigation was originally created
+    #[serde(rename = "CreationDate")]
+    pub creation_date: Option<String>,
+
+    /// The date/time that the mitigation was last modified This field needs to match the values retrieved to ensure data-concurrency
+    #[serde(rename = "LastUpdateDate")]
+    pub last_update_date: Option<String>,
+
+    /// The id of the project that the artifact belongs to
+    #[serde(rename = "ProjectId")]
+    pub project_id: Option<u64>,
+
+    /// The datetime used to track optimistic concurrency to prevent edit conflicts
+    #[serde(rename = "ConcurrencyDate")]
+    pub concurrency_date: Option<String>,
+}
+
+/// The Risk Artifact Submodule
+pub struct RiskClient<'a> {
+    client: HttpClient,
+    base_url: &'a str,
+}
+
+impl<'a> RiskClient<'a> {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
+        RiskClient { client, base_url }
+    }
+
+    /// Retrieves a page of the risks in the project.
+    /// Note: the starting row is 1 based
+    pub async fn list(
+        &self,
+        project_id: u64,
+        starting_row: u64,
+        number_of_rows: u64,
+    ) -> Response<Vec<RiskDto>> {
+        let path = &format!(
+            "/projects/{}/risks?starting_row={}&number_of_rows={}",
+            project_id, starting_row, number_of_rows
+        );
+
+        let risks = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<RiskDto>>()
+            .await?;
+
+        Ok(risks)
+    }
+
+    /// Streams the risks in the project, fetching them page by page
+    pub fn list_stream(
+        &self,
+        project_id: u64,
+        page_size: u64,
+    ) -> impl Stream<Item = Response<RiskDto>> + '_ {
+        stream::paged(page_size, move |starting_row, number_of_rows| {
+            self.list(project_id, starting_row, number_of_rows)
+        })
+    }
+
+    /// Retrieves a single risk in the system
+    pub async fn get(&self, project_id: u64, risk_id: u64) -> Response<RiskDto> {
+        let path = &format!("/projects/{}/risks/{}", project_id, risk_id);
+
+        let risk = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<RiskDto>()
+            .await?;
+
+        Ok(risk)
+    }
+
+    /// Creates a new risk in the specified project
+    pub async fn create(&self, project_id: u64, risk: RiskDto) -> Response<RiskDto> {
+        let json_risk = serde_json::to_string(&risk)?;
+        let risk = self
+            .client
+            .post(self.append_to_url(&format!("/projects/{}/risks", project_id)))
+            .body(json_risk)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<RiskDto>()
+            .await?;
+
+        Ok(risk)
+    }
+
+    /// Updates a risk in the system. When the risk was modified since it was retrieved,
+    /// fails with a ConflictError holding the current copy of the risk
+    pub async fn update(&self, project_id: u64, risk: RiskDto) -> Response<()> {
+        let risk_id = InvalidPayloadError::require_id(risk.risk_id, "RiskId")?;
+        let json_risk = serde_json::to_string(&risk)?;
+        let response = self
+            .client
+            .put(self.append_to_url(&format!("/projects/{}/risks/{}", project_id, risk_id)))
+            .body(json_risk)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::CONFLICT {
+            let current = self.get(project_id, risk_id).await?;
+            return Err(Box::new(ConflictError { current }));
+        }
+        response.error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Retrieves a risk, applies the changes and updates it, retrying on conflicts
+    /// with the current copy of the risk up to max_retries times
+    pub async fn update_with_retry<F>(
+        &self,
+        project_id: u64,
+        risk_id: u64,
+        max_retries: u32,
+        mut apply: F,
+    ) -> Response<()>
+    where
+        F: FnMut(&mut RiskDto),
+    {
+        let mut risk = self.get(project_id, risk_id).await?;
+        let mut retries = 0;
+
+        loop {
+            apply(&mut risk);
+
+            match self.update(project_id, risk).await {
+                Err(error) if retries < max_retries => {
+                    risk = error.downcast::<ConflictError<RiskDto>>()?.current;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Deletes a risk in the system
+    pub async fn delete(&self, project_id: u64, risk_id: u64) -> Response<()> {
+        self.client
+            .delete(self.append_to_url(&format!("/projects/{}/risks/{}", project_id, risk_id)))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Retrieves the mitigations of a risk, ordered by position
+    pub async fn mitigation_list(
+        &self,
+        project_id: u64,
+        risk_id: u64,
+    ) -> Response<Vec<RiskMitigationDto>> {
+        let path = &format!("/projects/{}/risks/{}/mitigations", project_id, risk_id);
+
+        let mitigations = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<RiskMitigationDto>>()
+            .await?;
+
+        Ok(mitigations)
+    }
+
+    /// Adds a mitigation to a risk, inserted before the existing mitigation when one
+    /// is provided, otherwise at the end of the list
+    pub async fn mitigation_create(
+        &self,
+        project_id: u64,
+        risk_id: u64,
+        mitigation: RiskMitigationDto,
+        existing_risk_mitigation_id: Option<u64>,
+    ) -> Response<RiskMitigationDto> {
+        let mut path = format!("/projects/{}/risks/{}/mitigations", project_id, risk_id);
+        if let Some(existing_risk_mitigation_id) = existing_risk_mitigation_id {
+            path.push_str(&format!(
+                "?existing_risk_mitigation_id={}",
+                existing_risk_mitigation_id
+            ));
+        }
+
+        let json_mitigation = serde_json::to_string(&mitigation)?;
+        let mitigation = self
+            .client
+            .post(self.append_to_url(&path))
+            .body(json_mitigation)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<RiskMitigationDto>()
+            .await?;
+
+        Ok(mitigation)
+    }
+
+    /// Updates a mitigation of a risk. When the mitigation was modified since it was retrieved,
+    /// fails with a ConflictError holding the current copy of the mitigation
+    pub async fn mitigation_update(
+        &self,
+        project_id: u64,
+        risk_id: u64,
+        mitigation: RiskMitigationDto,
+    ) -> Response<()> {
+        let risk_mitigation_id =
+            InvalidPayloadError::require_id(mitigation.risk_mitigation_id, "RiskMitigationId")?;
+        let json_mitigation = serde_json::to_string(&mitigation)?;
+        let response = self
+            .client
+            .put(self.append_to_url(&format!(
+                "/projects/{}/risks/{}/mitigations",
+                project_id, risk_id
+            )))
+            .body(json_mitigation)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::CONFLICT {
+            // there is no endpoint retrieving a single mitigation
+            let current = self
+                .mitigation_list(project_id, risk_id)
+                .await?
+                .into_iter()
+                .find(|current| current.risk_mitigation_id == Some(risk_mitigation_id))
+                .ok_or("the mitigation was deleted since it was retrieved")?;
+            return Err(Box::new(ConflictError { current }));
+        }
+        response.error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Deletes a mitigation of a risk
+    pub async fn mitigation_delete(
+        &self,
+        project_id: u64,
+        risk_id: u64,
+        risk_mitigation_id: u64,
+    ) -> Response<()> {
+        self.client
+            .delete(self.append_to_url(&format!(
+                "/projects/{}/risks/{}/mitigations/{}",
+                project_id, risk_id, risk_mitigation_id
+            )))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    fn append_to_url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
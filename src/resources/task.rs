@@ -1,8 +1,21 @@
-use reqwest::Client;
+use futures::Stream;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
-use crate::Response;
+use crate::{
+    batch::{self, BatchOptions, BatchResults},
+    error::{ConflictError, InvalidPayloadError},
+    hierarchy::{Parented, Tree},
+    resources::custom_property::{
+        ArtifactCustomPropertyDto, CustomProperties, CustomPropertyError, CustomPropertyValue,
+    },
+    stream,
+    transport::{HttpClient, StatusCode},
+    Response,
+};
+
+/// The number of times a patch is retried on concurrency conflicts
+const PATCH_MAX_RETRIES: u32 = 3;
 
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -89,16 +102,131 @@ pub struct TaskDto {
     /// The datetime used to track optimistic concurrency to prevent edit conflicts
     #[serde(rename = "ConcurrencyDate")]
     pub concurrency_date: Option<String>,
+
+    /// The values of the custom properties of the artifact
+    #[serde(rename = "CustomProperties")]
+    pub custom_properties: Option<Vec<ArtifactCustomPropertyDto>>,
+}
+
+impl CustomProperties for TaskDto {
+    fn custom_properties(&self) -> Option<&Vec<ArtifactCustomPropertyDto>> {
+        self.custom_properties.as_ref()
+    }
+
+    fn custom_properties_mut(&mut self) -> &mut Option<Vec<ArtifactCustomPropertyDto>> {
+        &mut self.custom_properties
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The Task Folder fields, folders being used to organise the tasks of a project
+pub struct TaskFolderDto {
+    /// The id of the task folder (integer)
+    #[serde(rename = "TaskFolderId")]
+    pub task_folder_id: Option<u64>,
+
+    /// The id of the parent folder, null for the top level folders (integer)
+    #[serde(rename = "ParentTaskFolderId")]
+    #[serialize_always]
+    pub parent_task_folder_id: Option<u64>,
+
+    /// The name of the task folder (string)
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    /// The id of the project that the folder belongs to
+    #[serde(rename = "ProjectId")]
+    pub project_id: Option<u64>,
+}
+
+impl Parented for TaskFolderDto {
+    fn id(&self) -> Option<u64> {
+        self.task_folder_id
+    }
+
+    fn parent_id(&self) -> Option<u64> {
+        self.parent_task_folder_id
+    }
+}
+
+pub type TaskFolderTree = Tree<TaskFolderDto>;
+
+/// A change to a single field of a task, applied by TaskClient::patch
+#[derive(Debug, Clone)]
+pub enum TaskChange {
+    /// The name of the task
+    Name(String),
+    /// The description of the task
+    Description(Option<String>),
+    /// The id of the status of the task
+    Status(u64),
+    /// The id of the type of the task
+    Type(u64),
+    /// The id of the priority of the task
+    Priority(Option<u64>),
+    /// The id of the user the task is assigned to
+    Owner(Option<u64>),
+    /// The id of the release the task is scheduled for
+    Release(Option<u64>),
+    /// The id of the requirement the task belongs to
+    Requirement(Option<u64>),
+    /// The id of the folder the task is in
+    Folder(Option<u64>),
+    /// The start date of the task
+    StartDate(Option<String>),
+    /// The end date of the task
+    EndDate(Option<String>),
+    /// The estimated effort in minutes
+    EstimatedEffort(Option<u64>),
+    /// The actual effort in minutes
+    ActualEffort(Option<u64>),
+    /// The remaining effort in minutes
+    RemainingEffort(Option<u64>),
+    /// Sets, or clears when None, the value of a custom property by its number
+    CustomProperty {
+        property_number: u64,
+        value: Option<CustomPropertyValue>,
+    },
+}
+
+impl TaskChange {
+    /// Applies the change to the task, failing on the custom property values
+    /// that can't be sent
+    pub fn apply(&self, task: &mut TaskDto) -> Result<(), CustomPropertyError> {
+        match self {
+            TaskChange::Name(value) => task.name = Some(value.clone()),
+            TaskChange::Description(value) => task.description = value.clone(),
+            TaskChange::Status(value) => task.task_status_id = Some(*value),
+            TaskChange::Type(value) => task.task_type_id = Some(*value),
+            TaskChange::Priority(value) => task.task_priority_id = *value,
+            TaskChange::Owner(value) => task.owner_id = *value,
+            TaskChange::Release(value) => task.release_id = *value,
+            TaskChange::Requirement(value) => task.requirement_id = *value,
+            TaskChange::Folder(value) => task.task_folder_id = *value,
+            TaskChange::StartDate(value) => task.start_date = value.clone(),
+            TaskChange::EndDate(value) => task.end_date = value.clone(),
+            TaskChange::EstimatedEffort(value) => task.estimated_effort = *value,
+            TaskChange::ActualEffort(value) => task.actual_effort = *value,
+            TaskChange::RemainingEffort(value) => task.remaining_effort = *value,
+            TaskChange::CustomProperty {
+                property_number,
+                value,
+            } => return task.set_custom_property_value(*property_number, value.clone()),
+        }
+
+        Ok(())
+    }
 }
 
 /// The Task Artifact Submodule
 pub struct TaskClient<'a> {
-    client: Client,
+    client: HttpClient,
     base_url: &'a str,
 }
 
 impl<'a> TaskClient<'a> {
-    pub fn new(client: Client, base_url: &'a str) -> Self {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
         TaskClient { client, base_url }
     }
 
@@ -115,6 +243,12 @@ impl<'a> TaskClient<'a> {
         Ok(tasks)
     }
 
+    /// Streams the tasks owned by the currently authenticated user, deserializing
+    /// each one as soon as it is received
+    pub fn list_my_stream(&self) -> impl Stream<Item = Response<TaskDto>> {
+        stream::json_array(self.client.get(self.append_to_url("/tasks")))
+    }
+
     /// Retrieves a single task in the system
     pub async fn get(&self, project_id: u64, task_id: u64) -> Response<TaskDto> {
         let path = &format!("/projects/{}/tasks/{}", project_id, task_id);
@@ -139,35 +273,451 @@ impl<'a> TaskClient<'a> {
             .body(json_task)
             .send()
             .await?
+            .error_for_status()?
             .json::<TaskDto>()
             .await?;
 
         Ok(task)
     }
 
-    /// Updates a task in the system
+    /// Updates a task in the system. When the task was modified since it was retrieved,
+    /// fails with a ConflictError holding the current copy of the task
     pub async fn update(&self, project_id: u64, task: TaskDto) -> Response<()> {
+        let task_id = InvalidPayloadError::require_id(task.task_id, "TaskId")?;
         let json_task = serde_json::to_string(&task)?;
-        self.client
-            .put(self.append_to_url(&format!("/projects/{}/tasks", project_id)))
+        let response = self
+            .client
+            .put(self.append_to_url(&format!("/projects/{}/tasks/{}", project_id, task_id)))
             .body(json_task)
             .send()
             .await?;
 
+        if response.status() == StatusCode::CONFLICT {
+            let current = self.get(project_id, task_id).await?;
+            return Err(Box::new(ConflictError { current }));
+        }
+        response.error_for_status()?;
+
         Ok(())
     }
 
+    /// Retrieves a task, applies the changes and updates it, retrying on conflicts
+    /// with the current copy of the task up to max_retries times
+    pub async fn update_with_retry<F>(
+        &self,
+        project_id: u64,
+        task_id: u64,
+        max_retries: u32,
+        mut apply: F,
+    ) -> Response<()>
+    where
+        F: FnMut(&mut TaskDto),
+    {
+        let mut task = self.get(project_id, task_id).await?;
+        let mut retries = 0;
+
+        loop {
+            apply(&mut task);
+
+            match self.update(project_id, task).await {
+                Err(error) if retries < max_retries => {
+                    task = error.downcast::<ConflictError<TaskDto>>()?.current;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Applies the changes to the latest version of the task on the server, leaving
+    /// its other fields untouched. Concurrency conflicts are retried on the fresh copy
+    pub async fn patch(
+        &self,
+        project_id: u64,
+        task_id: u64,
+        changes: &[TaskChange],
+    ) -> Response<()> {
+        // the changes only fail on the custom property values that can't be sent,
+        // whatever the task they are applied to
+        let mut checked = TaskDto::default();
+        for change in changes {
+            change.apply(&mut checked)?;
+        }
+
+        self.update_with_retry(project_id, task_id, PATCH_MAX_RETRIES, |task| {
+            for change in changes {
+                change
+                    .apply(task)
+                    .expect("the changes were checked before the update");
+            }
+        })
+        .await
+    }
+
     /// Deletes a task in the system
     pub async fn delete(&self, project_id: u64, task_id: u64) -> Response<()> {
         self.client
             .delete(self.append_to_url(&format!("/projects/{}/tasks/{}", project_id, task_id)))
             .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Retrieves all the task folders of the project
+    pub async fn folder_list(&self, project_id: u64) -> Response<Vec<TaskFolderDto>> {
+        let path = &format!("/projects/{}/task-folders", project_id);
+
+        let folders = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<TaskFolderDto>>()
             .await?;
 
+        Ok(folders)
+    }
+
+    /// Retrieves all the task folders of the project as a tree
+    pub async fn folder_tree(&self, project_id: u64) -> Response<TaskFolderTree> {
+        let folders = self.folder_list(project_id).await?;
+
+        Ok(Tree::from_parent_ids(folders))
+    }
+
+    /// Retrieves a single task folder
+    pub async fn folder_get(
+        &self,
+        project_id: u64,
+        task_folder_id: u64,
+    ) -> Response<TaskFolderDto> {
+        let path = &format!("/projects/{}/task-folders/{}", project_id, task_folder_id);
+
+        let folder = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<TaskFolderDto>()
+            .await?;
+
+        Ok(folder)
+    }
+
+    /// Creates a new task folder, under the parent folder when one is set
+    pub async fn folder_create(
+        &self,
+        project_id: u64,
+        folder: TaskFolderDto,
+    ) -> Response<TaskFolderDto> {
+        let json_folder = serde_json::to_string(&folder)?;
+        let folder = self
+            .client
+            .post(self.append_to_url(&format!("/projects/{}/task-folders", project_id)))
+            .body(json_folder)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<TaskFolderDto>()
+            .await?;
+
+        Ok(folder)
+    }
+
+    /// Updates a task folder
+    pub async fn folder_update(&self, project_id: u64, folder: TaskFolderDto) -> Response<()> {
+        let folder_id = InvalidPayloadError::require_id(folder.task_folder_id, "TaskFolderId")?;
+        let json_folder = serde_json::to_string(&folder)?;
+        self.client
+            .put(self.append_to_url(&format!(
+                "/projects/{}/task-folders/{}",
+                project_id, folder_id
+            )))
+            .body(json_folder)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Renames a task folder
+    pub async fn folder_rename(
+        &self,
+        project_id: u64,
+        task_folder_id: u64,
+        name: &str,
+    ) -> Response<()> {
+        let mut folder = self.folder_get(project_id, task_folder_id).await?;
+        folder.name = name.to_owned();
+
+        self.folder_update(project_id, folder).await
+    }
+
+    /// Moves a task folder with its content under another folder,
+    /// or to the top level when no parent folder is provided
+    pub async fn folder_move(
+        &self,
+        project_id: u64,
+        task_folder_id: u64,
+        parent_task_folder_id: Option<u64>,
+    ) -> Response<()> {
+        let mut folder = self.folder_get(project_id, task_folder_id).await?;
+        folder.parent_task_folder_id = parent_task_folder_id;
+
+        self.folder_update(project_id, folder).await
+    }
+
+    /// Deletes a task folder, its tasks are moved to the top level
+    pub async fn folder_delete(&self, project_id: u64, task_folder_id: u64) -> Response<()> {
+        self.client
+            .delete(self.append_to_url(&format!(
+                "/projects/{}/task-folders/{}",
+                project_id, task_folder_id
+            )))
+            .send()
+            .await?
+            .error_for_status()?;
+
         Ok(())
     }
 
+    /// Retrieves a page of the tasks in a task folder.
+    /// Note: the starting row is 1 based
+    pub async fn folder_task_list(
+        &self,
+        project_id: u64,
+        task_folder_id: u64,
+        starting_row: u64,
+        number_of_rows: u64,
+    ) -> Response<Vec<TaskDto>> {
+        let path = &format!(
+            "/projects/{}/task-folders/{}/tasks?starting_row={}&number_of_rows={}",
+            project_id, task_folder_id, starting_row, number_of_rows
+        );
+
+        let tasks = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<TaskDto>>()
+            .await?;
+
+        Ok(tasks)
+    }
+
+    /// Creates the tasks of the batch, the results holding the ids assigned by the server
+    pub async fn create_batch(
+        &self,
+        project_id: u64,
+        tasks: Vec<TaskDto>,
+        options: &BatchOptions,
+    ) -> BatchResults {
+        batch::run(tasks, options, |task| async move {
+            batch::validate(&task, missing_fields(&task, false))?;
+            if options.dry_run {
+                return Ok(None);
+            }
+
+            Ok(self.create(project_id, task).await?.task_id)
+        })
+        .await
+    }
+
+    /// Updates the tasks of the batch
+    pub async fn update_batch(
+        &self,
+        project_id: u64,
+        tasks: Vec<TaskDto>,
+        options: &BatchOptions,
+    ) -> BatchResults {
+        batch::run(tasks, options, |task| async move {
+            batch::validate(&task, missing_fields(&task, true))?;
+            let task_id = task.task_id;
+            if !options.dry_run {
+                self.update(project_id, task).await?;
+            }
+
+            Ok(task_id)
+        })
+        .await
+    }
+
+    /// Deletes the tasks of the batch
+    pub async fn delete_batch(
+        &self,
+        project_id: u64,
+        task_ids: Vec<u64>,
+        options: &BatchOptions,
+    ) -> BatchResults {
+        batch::run(task_ids, options, |task_id| async move {
+            if !options.dry_run {
+                self.delete(project_id, task_id).await?;
+            }
+
+            Ok(Some(task_id))
+        })
+        .await
+    }
+
     fn append_to_url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
 }
+
+/// The fields Spira requires on a task, the id being required on updates
+fn missing_fields(task: &TaskDto, update: bool) -> Vec<&'static str> {
+    let mut missing_fields = Vec::new();
+    if task.name.as_deref().unwrap_or_default().is_empty() {
+        missing_fields.push("Name");
+    }
+    if update && task.task_id.is_none() {
+        missing_fields.push("TaskId");
+    }
+
+    missing_fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{
+        recording::{RecordingTransport, Reply},
+        HttpRequest, Method,
+    };
+
+    fn server_task() -> TaskDto {
+        TaskDto {
+            task_id: Some(7),
+            name: Some("server".to_string()),
+            description: Some("kept".to_string()),
+            task_priority_id: Some(2),
+            concurrency_date: Some("2022-03-14T11:28:07.240".to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Answers the gets with the server copy of the task and the puts with the status
+    fn task_server(put_status: StatusCode) -> impl Fn(&HttpRequest) -> Reply {
+        move |request| match request.method {
+            Method::Get => Reply::json(&server_task()),
+            _ => Reply::status(put_status),
+        }
+    }
+
+    fn sent_task(request: &HttpRequest) -> TaskDto {
+        serde_json::from_str(request.body.as_deref().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn update_puts_to_the_task_url() {
+        let transport = RecordingTransport::new(task_server(StatusCode::OK));
+
+        transport
+            .client()
+            .task
+            .update(1, server_task())
+            .await
+            .unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::Put);
+        assert_eq!(requests[0].url, "http://spira/projects/1/tasks/7");
+        assert_eq!(sent_task(&requests[0]).task_id, Some(7));
+    }
+
+    #[tokio::test]
+    async fn update_conflicts_carry_the_server_copy() {
+        let transport = RecordingTransport::new(task_server(StatusCode::CONFLICT));
+        let task = TaskDto {
+            name: Some("local".to_string()),
+            ..server_task()
+        };
+
+        let error = transport.client().task.update(1, task).await.unwrap_err();
+
+        let conflict = error.downcast::<ConflictError<TaskDto>>().unwrap();
+        assert_eq!(conflict.current.name.as_deref(), Some("server"));
+        assert_eq!(
+            transport.requests()[1].url,
+            "http://spira/projects/1/tasks/7"
+        );
+    }
+
+    #[tokio::test]
+    async fn update_with_retry_stops_after_max_retries() {
+        let transport = RecordingTransport::new(task_server(StatusCode::CONFLICT));
+
+        let error = transport
+            .client()
+            .task
+            .update_with_retry(1, 7, 2, |task| task.name = Some("local".to_string()))
+            .await
+            .unwrap_err();
+
+        assert!(error.downcast::<ConflictError<TaskDto>>().is_ok());
+        assert_eq!(transport.requests_with(Method::Put).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn update_with_retry_applies_the_changes_to_the_server_copy() {
+        let transport = RecordingTransport::new(task_server(StatusCode::OK));
+
+        transport
+            .client()
+            .task
+            .update_with_retry(1, 7, 2, |task| task.name = Some("local".to_string()))
+            .await
+            .unwrap();
+
+        let puts = transport.requests_with(Method::Put);
+        assert_eq!(puts.len(), 1);
+        let sent = sent_task(&puts[0]);
+        assert_eq!(sent.name.as_deref(), Some("local"));
+        assert_eq!(sent.description.as_deref(), Some("kept"));
+    }
+
+    #[tokio::test]
+    async fn patch_changes_only_the_listed_fields() {
+        let transport = RecordingTransport::new(task_server(StatusCode::OK));
+        let changes = [
+            TaskChange::Name("patched".to_string()),
+            TaskChange::Owner(Some(3)),
+            TaskChange::CustomProperty {
+                property_number: 1,
+                value: Some(CustomPropertyValue::Integer(5)),
+            },
+        ];
+
+        transport.client().task.patch(1, 7, &changes).await.unwrap();
+
+        let puts = transport.requests_with(Method::Put);
+        assert_eq!(puts.len(), 1);
+        let sent = sent_task(&puts[0]);
+        let server = server_task();
+        assert_eq!(sent.name.as_deref(), Some("patched"));
+        assert_eq!(sent.owner_id, Some(3));
+        assert_eq!(sent.custom_properties.unwrap()[0].integer_value, Some(5));
+        assert_eq!(sent.description, server.description);
+        assert_eq!(sent.task_priority_id, server.task_priority_id);
+        assert_eq!(sent.concurrency_date, server.concurrency_date);
+    }
+
+    #[tokio::test]
+    async fn patch_sends_nothing_for_invalid_changes() {
+        let transport = RecordingTransport::new(task_server(StatusCode::OK));
+        let changes = [TaskChange::CustomProperty {
+            property_number: 1,
+            value: Some(CustomPropertyValue::User(u64::MAX)),
+        }];
+
+        let result = transport.client().task.patch(1, 7, &changes).await;
+
+        assert!(result.is_err());
+        assert!(transport.requests().is_empty());
+    }
+}
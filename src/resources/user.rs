@@ -1,26 +1,105 @@
-use reqwest::Client;
+use futures::Stream;
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 
-use crate::Response;
+use crate::{error::InvalidPayloadError, stream, transport::HttpClient, Response};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+/// The User fields
 pub struct UserDto {
+    /// The id of the user (integer)
     #[serde(rename = "UserId")]
     pub user_id: Option<u64>,
+
+    /// The first name of the user (string - required for POST)
+    #[serde(rename = "FirstName")]
+    pub first_name: Option<String>,
+
+    /// The middle initial of the user (string)
+    #[serde(rename = "MiddleInitial")]
+    pub middle_initial: Option<String>,
+
+    /// The last name of the user (string - required for POST)
+    #[serde(rename = "LastName")]
+    pub last_name: Option<String>,
+
+    /// The full name of the user, made up of the first, middle and last names (string - read only)
     #[serde(rename = "FullName")]
     pub full_name: Option<String>,
+
+    /// The login of the user (string - required for POST)
+    #[serde(rename = "UserName")]
+    pub user_name: Option<String>,
+
+    /// The email address of the user (string)
+    #[serde(rename = "EmailAddress")]
+    pub email_address: Option<String>,
+
+    /// The department of the user (string)
+    #[serde(rename = "Department")]
+    pub department: Option<String>,
+
+    /// Is the user a system administrator (boolean)
+    #[serde(rename = "Admin")]
+    pub admin: Option<bool>,
+
+    /// Is the user active, inactive users can't log in (boolean)
+    #[serde(rename = "Active")]
+    pub active: Option<bool>,
+
+    /// Has the user registration been approved (boolean)
+    #[serde(rename = "Approved")]
+    pub approved: Option<bool>,
+
+    /// Is the user locked out after too many failed log in attempts (boolean)
+    #[serde(rename = "Locked")]
+    pub locked: Option<bool>,
+
+    /// The distinguished name of the user, for users authenticated against LDAP (string)
+    #[serde(rename = "LdapDn")]
+    pub ldap_dn: Option<String>,
+
+    /// The token used to access the user RSS feeds (string)
+    #[serde(rename = "RssToken")]
+    pub rss_token: Option<String>,
+
+    /// The id of the role of the user in the project, set when listing the project users (integer)
+    #[serde(rename = "ProjectRoleId")]
+    pub project_role_id: Option<u64>,
+
+    /// The name of the role of the user in the project, set when listing the project users (string)
+    #[serde(rename = "ProjectRoleName")]
+    pub project_role_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+/// The membership of a user in a project
+pub struct ProjectUserDto {
+    /// The id of the project (integer)
+    #[serde(rename = "ProjectId")]
+    pub project_id: u64,
+
+    /// The id of the user (integer)
+    #[serde(rename = "UserId")]
+    pub user_id: u64,
+
+    /// The id of the role of the user in the project (integer)
+    #[serde(rename = "ProjectRoleId")]
+    pub project_role_id: u64,
 }
 
 pub struct UserClient<'a> {
-    client: Client,
+    client: HttpClient,
     base_url: &'a str,
 }
 
 impl<'a> UserClient<'a> {
-    pub fn new(client: Client, base_url: &'a str) -> Self {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
         UserClient { client, base_url }
     }
 
+    /// Retrieves the members of a project, with their project role
     pub async fn list(&self, project_id: u64) -> Response<Vec<UserDto>> {
         let users = self
             .client
@@ -33,7 +112,173 @@ impl<'a> UserClient<'a> {
         Ok(users)
     }
 
+    /// Streams the members of a project, deserializing each one as soon as it is received
+    pub fn list_stream(&self, project_id: u64) -> impl Stream<Item = Response<UserDto>> {
+        let path = &format!("/projects/{}/users", project_id);
+
+        stream::json_array(self.client.get(self.append_to_url(path)))
+    }
+
+    /// Retrieves the currently authenticated user
+    pub async fn current(&self) -> Response<UserDto> {
+        let user = self
+            .client
+            .get(self.append_to_url("/users"))
+            .send()
+            .await?
+            .json::<UserDto>()
+            .await?;
+
+        Ok(user)
+    }
+
+    /// Retrieves a single user by id
+    pub async fn get(&self, user_id: u64) -> Response<UserDto> {
+        let user = self
+            .client
+            .get(self.append_to_url(&format!("/users/{}", user_id)))
+            .send()
+            .await?
+            .json::<UserDto>()
+            .await?;
+
+        Ok(user)
+    }
+
+    /// Retrieves a single user by login
+    pub async fn get_by_user_name(&self, user_name: &str) -> Response<UserDto> {
+        let user = self
+            .client
+            .get(self.append_to_url(&format!(
+                "/users/usernames/{}",
+                encode_path_segment(user_name)
+            )))
+            .send()
+            .await?
+            .json::<UserDto>()
+            .await?;
+
+        Ok(user)
+    }
+
+    /// Creates a new user, the password is ignored for users authenticated against LDAP
+    pub async fn create(
+        &self,
+        user: UserDto,
+        password: &str,
+        password_question: &str,
+        password_answer: &str,
+    ) -> Response<UserDto> {
+        let json_user = serde_json::to_string(&user)?;
+        let user = self
+            .client
+            .post(self.append_to_url("/users"))
+            .query(&[
+                ("password", password),
+                ("password_question", password_question),
+                ("password_answer", password_answer),
+            ])
+            .body(json_user)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<UserDto>()
+            .await?;
+
+        Ok(user)
+    }
+
+    /// Updates a user
+    pub async fn update(&self, user: UserDto) -> Response<()> {
+        let user_id = InvalidPayloadError::require_id(user.user_id, "UserId")?;
+        let json_user = serde_json::to_string(&user)?;
+        self.client
+            .put(self.append_to_url(&format!("/users/{}", user_id)))
+            .body(json_user)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Deactivates a user, Spira keeps the user on the artifacts it created or owns
+    pub async fn deactivate(&self, user_id: u64) -> Response<()> {
+        let mut user = self.get(user_id).await?;
+        user.active = Some(false);
+
+        self.update(user).await
+    }
+
+    /// Adds a user to a project with the given project role
+    pub async fn member_add(
+        &self,
+        project_id: u64,
+        user_id: u64,
+        project_role_id: u64,
+    ) -> Response<()> {
+        let json_member = serde_json::to_string(&ProjectUserDto {
+            project_id,
+            user_id,
+            project_role_id,
+        })?;
+        self.client
+            .post(self.append_to_url(&format!("/projects/{}/users", project_id)))
+            .body(json_member)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Changes the project role of a member of a project
+    pub async fn member_update(
+        &self,
+        project_id: u64,
+        user_id: u64,
+        project_role_id: u64,
+    ) -> Response<()> {
+        let json_member = serde_json::to_string(&ProjectUserDto {
+            project_id,
+            user_id,
+            project_role_id,
+        })?;
+        self.client
+            .put(self.append_to_url(&format!("/projects/{}/users", project_id)))
+            .body(json_member)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Removes a user from a project
+    pub async fn member_remove(&self, project_id: u64, user_id: u64) -> Response<()> {
+        self.client
+            .delete(self.append_to_url(&format!("/projects/{}/users/{}", project_id, user_id)))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
     fn append_to_url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
 }
+
+/// Percent-encodes a value put in a url path, leaving only the unreserved characters as is
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::{transport::HttpClient, Response};
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// A workflow transition moving an artifact from one status to another
+pub struct WorkflowTransitionDto {
+    /// The id of the workflow the transition belongs to (integer)
+    #[serde(rename = "WorkflowId")]
+    pub workflow_id: Option<u64>,
+
+    /// The id of the transition (integer)
+    #[serde(rename = "TransitionId")]
+    pub transition_id: Option<u64>,
+
+    /// The name of the transition, as displayed on the workflow buttons (string)
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    /// The id of the status the artifact is moved from (integer)
+    #[serde(
+        rename = "StatusId_Input",
+        alias = "IncidentStatusId_Input",
+        alias = "TaskStatusId_Input",
+        alias = "RequirementStatusId_Input"
+    )]
+    pub status_id_input: Option<u64>,
+
+    /// The name of the status the artifact is moved from (string)
+    #[serde(
+        rename = "StatusName_Input",
+        alias = "IncidentStatusName_Input",
+        alias = "TaskStatusName_Input",
+        alias = "RequirementStatusName_Input"
+    )]
+    pub status_name_input: Option<String>,
+
+    /// The id of the status the artifact is moved to (integer)
+    #[serde(
+        rename = "StatusId_Output",
+        alias = "IncidentStatusId_Output",
+        alias = "TaskStatusId_Output",
+        alias = "RequirementStatusId_Output"
+    )]
+    pub status_id_output: Option<u64>,
+
+    /// The name of the status the artifact is moved to (string)
+    #[serde(
+        rename = "StatusName_Output",
+        alias = "IncidentStatusName_Output",
+        alias = "TaskStatusName_Output",
+        alias = "RequirementStatusName_Output"
+    )]
+    pub status_name_output: Option<String>,
+
+    /// Can the transition be executed by the user that created the artifact,
+    /// the detector in the case of incidents (boolean)
+    #[serde(rename = "ExecuteByCreator", alias = "ExecuteByDetector")]
+    pub execute_by_creator: Option<bool>,
+
+    /// Can the transition be executed by the user the artifact is assigned-to (boolean)
+    #[serde(rename = "ExecuteByOwner")]
+    pub execute_by_owner: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// The state of an artifact field for a given type and status
+pub struct WorkflowFieldDto {
+    /// The id of the field (integer)
+    #[serde(rename = "FieldId")]
+    pub field_id: Option<u64>,
+
+    /// The name of the field, matching the DTO property name, e.g. "OwnerId" (string)
+    #[serde(rename = "FieldName")]
+    pub field_name: Option<String>,
+
+    /// The display name of the field (string)
+    #[serde(rename = "FieldCaption")]
+    pub field_caption: Option<String>,
+
+    /// The state of the field (integer)
+    /// Relevant values: Inactive 1; Required 2; Hidden 3
+    #[serde(rename = "FieldStateId")]
+    pub field_state_id: Option<u64>,
+}
+
+impl WorkflowFieldDto {
+    /// Is the field read only in this status
+    pub fn is_disabled(&self) -> bool {
+        self.field_state_id == Some(1)
+    }
+
+    /// Must the field be filled in before moving to this status
+    pub fn is_required(&self) -> bool {
+        self.field_state_id == Some(2)
+    }
+
+    /// Is the field hidden in this status
+    pub fn is_hidden(&self) -> bool {
+        self.field_state_id == Some(3)
+    }
+}
+
+/// The Workflow Submodule, exposing the transitions and field rules of the incident,
+/// task and requirement workflows. The transitions returned are the ones the authenticated
+/// user's project role is allowed to execute.
+pub struct WorkflowClient<'a> {
+    client: HttpClient,
+    base_url: &'a str,
+}
+
+impl<'a> WorkflowClient<'a> {
+    pub fn new(client: HttpClient, base_url: &'a str) -> Self {
+        WorkflowClient { client, base_url }
+    }
+
+    /// Retrieves the transitions available to an incident of the given type and status
+    pub async fn incident_transition_list(
+        &self,
+        project_id: u64,
+        incident_type_id: u64,
+        incident_status_id: u64,
+        is_detector: bool,
+        is_owner: bool,
+    ) -> Response<Vec<WorkflowTransitionDto>> {
+        self.transition_list(
+            &format!("/projects/{}/incidents", project_id),
+            &format!(
+                "current_type_id={}&current_status_id={}&is_detector={}&is_owner={}",
+                incident_type_id, incident_status_id, is_detector, is_owner
+            ),
+        )
+        .await
+    }
+
+    /// Retrieves the state of the incident fields for the given type and status
+    pub async fn incident_field_list(
+        &self,
+        project_id: u64,
+        incident_type_id: u64,
+        incident_status_id: u64,
+    ) -> Response<Vec<WorkflowFieldDto>> {
+        self.field_list(
+            &format!("/projects/{}/incidents", project_id),
+            incident_type_id,
+            incident_status_id,
+        )
+        .await
+    }
+
+    /// Retrieves the transitions available to a task of the given type and status
+    pub async fn task_transition_list(
+        &self,
+        project_id: u64,
+        task_type_id: u64,
+        task_status_id: u64,
+        is_creator: bool,
+        is_owner: bool,
+    ) -> Response<Vec<WorkflowTransitionDto>> {
+        self.transition_list(
+            &format!("/projects/{}/tasks", project_id),
+            &format!(
+                "current_type_id={}&current_status_id={}&is_creator={}&is_owner={}",
+                task_type_id, task_status_id, is_creator, is_owner
+            ),
+        )
+        .await
+    }
+
+    /// Retrieves the state of the task fields for the given type and status
+    pub async fn task_field_list(
+        &self,
+        project_id: u64,
+        task_type_id: u64,
+        task_status_id: u64,
+    ) -> Response<Vec<WorkflowFieldDto>> {
+        self.field_list(
+            &format!("/projects/{}/tasks", project_id),
+            task_type_id,
+            task_status_id,
+        )
+        .await
+    }
+
+    /// Retrieves the transitions available to a requirement of the given type and status
+    pub async fn requirement_transition_list(
+        &self,
+        project_id: u64,
+        requirement_type_id: i64,
+        requirement_status_id: u64,
+        is_creator: bool,
+        is_owner: bool,
+    ) -> Response<Vec<WorkflowTransitionDto>> {
+        self.transition_list(
+            &format!("/projects/{}/requirements", project_id),
+            &format!(
+                "current_type_id={}&current_status_id={}&is_creator={}&is_owner={}",
+                requirement_type_id, requirement_status_id, is_creator, is_owner
+            ),
+        )
+        .await
+    }
+
+    /// Retrieves the state of the requirement fields for the given type and status
+    pub async fn requirement_field_list(
+        &self,
+        project_id: u64,
+        requirement_type_id: i64,
+        requirement_status_id: u64,
+    ) -> Response<Vec<WorkflowFieldDto>> {
+        self.field_list(
+            &format!("/projects/{}/requirements", project_id),
+            requirement_type_id,
+            requirement_status_id,
+        )
+        .await
+    }
+
+    async fn transition_list(
+        &self,
+        artifact_path: &str,
+        query: &str,
+    ) -> Response<Vec<WorkflowTransitionDto>> {
+        let path = &format!("{}/workflow/transitions?{}", artifact_path, query);
+
+        let transitions = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<WorkflowTransitionDto>>()
+            .await?;
+
+        Ok(transitions)
+    }
+
+    async fn field_list(
+        &self,
+        artifact_path: &str,
+        type_id: impl std::fmt::Display,
+        status_id: u64,
+    ) -> Response<Vec<WorkflowFieldDto>> {
+        let path = &format!(
+            "{}/workflow/fields?current_type_id={}&current_status_id={}",
+            artifact_path, type_id, status_id
+        );
+
+        let fields = self
+            .client
+            .get(self.append_to_url(path))
+            .send()
+            .await?
+            .json::<Vec<WorkflowFieldDto>>()
+            .await?;
+
+        Ok(fields)
+    }
+
+    fn append_to_url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
@@ -0,0 +1,376 @@
+//! Streaming helpers
+//! The list endpoints return JSON arrays that can hold thousands of artifacts. Instead of
+//! buffering them, the streams deserialize each element as soon as its bytes arrive, or
+//! fetch the paged endpoints one page at a time, so consumers can stop early.
+
+use std::{collections::VecDeque, future::Future};
+
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    transport::{BodyStream, HttpResponse, RequestBuilder},
+    Response,
+};
+
+/// Sends the request and streams the elements of the JSON array it returns
+pub(crate) fn json_array<T>(request: RequestBuilder) -> impl Stream<Item = Response<T>>
+where
+    T: DeserializeOwned + 'static,
+{
+    stream::once(send(request))
+        .map_ok(|response| elements(response.bytes_stream()))
+        .try_flatten()
+}
+
+async fn send(request: RequestBuilder) -> Response<HttpResponse> {
+    request.send().await?.error_for_status()
+}
+
+/// Streams the items of a paged endpoint, fetching the next page once the current one
+/// is consumed. The last page is the first one with less than page_size items
+pub(crate) fn paged<'a, T, F, Fut>(
+    page_size: u64,
+    mut fetch: F,
+) -> impl Stream<Item = Response<T>> + 'a
+where
+    T: 'a,
+    F: FnMut(u64, u64) -> Fut + 'a,
+    Fut: Future<Output = Response<Vec<T>>> + 'a,
+{
+    let page_size = page_size.max(1);
+
+    stream::unfold(Some(1), move |starting_row: Option<u64>| {
+        let page = starting_row.map(|starting_row| (starting_row, fetch(starting_row, page_size)));
+
+        async move {
+            let (starting_row, page) = page?;
+            let (items, next_row) = match page.await {
+                Ok(items) => {
+                    let next_row = if items.len() as u64 == page_size {
+                        Some(starting_row + page_size)
+                    } else {
+                        None
+                    };
+                    (items.into_iter().map(Ok).collect(), next_row)
+                }
+                Err(error) => (vec![Err(error)], None),
+            };
+
+            Some((stream::iter(items), next_row))
+        }
+    })
+    .flatten()
+}
+
+/// Deserializes the elements of a JSON array from the chunks of a response body
+fn elements<T>(body: BodyStream) -> impl Stream<Item = Response<T>>
+where
+    T: DeserializeOwned,
+{
+    let state = (
+        body,
+        ArraySplitter::default(),
+        VecDeque::<Vec<u8>>::new(),
+        false,
+    );
+
+    stream::unfold(
+        state,
+        |(mut body, mut splitter, mut pending, mut finished)| async move {
+            loop {
+                if let Some(element) = pending.pop_front() {
+                    let item = serde_json::from_slice::<T>(&element).map_err(Into::into);
+                    return Some((item, (body, splitter, pending, finished)));
+                }
+                if finished {
+                    return None;
+                }
+
+                let error = match body.next().await {
+                    Some(Ok(chunk)) => match splitter.push(&chunk) {
+                        Ok(elements) => {
+                            pending.extend(elements);
+                            continue;
+                        }
+                        Err(error) => error,
+                    },
+                    Some(Err(error)) => error,
+                    None if splitter.is_complete() => {
+                        finished = true;
+                        continue;
+                    }
+                    None => "the response ended before the end of the JSON array".into(),
+                };
+
+                finished = true;
+                return Some((Err(error), (body, splitter, pending, finished)));
+            }
+        },
+    )
+}
+
+/// Splits a JSON array received in chunks into the bytes of its elements, keeping only
+/// the bytes of the element being received
+#[derive(Debug, Default)]
+struct ArraySplitter {
+    buffer: Vec<u8>,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    complete: bool,
+    element_count: usize,
+}
+
+impl ArraySplitter {
+    /// Adds a chunk, returning the elements it completes
+    fn push(&mut self, chunk: &[u8]) -> Response<Vec<Vec<u8>>> {
+        let mut elements = Vec::new();
+
+        for &byte in chunk {
+            if self.complete {
+                if byte.is_ascii_whitespace() {
+                    continue;
+                }
+                return Err("unexpected data after the end of the JSON array".into());
+            }
+
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                }
+                self.buffer.push(byte);
+                continue;
+            }
+
+            match (byte, self.depth) {
+                (b'[', 0) => {
+                    self.depth = 1;
+                    continue;
+                }
+                (_, 0) if byte.is_ascii_whitespace() => continue,
+                (_, 0) => return Err("the response is not a JSON array".into()),
+                (b',', 1) => {
+                    elements.push(self.take_element()?);
+                    continue;
+                }
+                (b']', 1) => {
+                    // only an empty array has no element before its closing bracket
+                    if self.element_count > 0 || !self.buffer.iter().all(u8::is_ascii_whitespace) {
+                        elements.push(self.take_element()?);
+                    }
+                    self.depth = 0;
+                    self.complete = true;
+                    continue;
+                }
+                (b'"', _) => self.in_string = true,
+                (b'[' | b'{', _) => self.depth += 1,
+                (b']' | b'}', _) => self.depth -= 1,
+                _ => {}
+            }
+            self.buffer.push(byte);
+        }
+
+        Ok(elements)
+    }
+
+    fn take_element(&mut self) -> Response<Vec<u8>> {
+        if self.buffer.iter().all(u8::is_ascii_whitespace) {
+            return Err("empty element in the JSON array".into());
+        }
+
+        self.element_count += 1;
+        Ok(std::mem::take(&mut self.buffer))
+    }
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        resources::requirement::RequirementDto,
+        transport::{
+            recording::{RecordingTransport, Reply},
+            HttpRequest, StatusCode,
+        },
+    };
+
+    /// Pushes the chunks, returning the elements and whether the array is complete
+    fn split(chunks: &[&[u8]]) -> Response<(Vec<String>, bool)> {
+        let mut splitter = ArraySplitter::default();
+        let mut elements = Vec::new();
+        for chunk in chunks {
+            elements.extend(splitter.push(chunk)?);
+        }
+        let elements = elements
+            .into_iter()
+            .map(|element| String::from_utf8(element).unwrap())
+            .collect();
+
+        Ok((elements, splitter.is_complete()))
+    }
+
+    /// Pushes the body one byte at a time
+    fn split_bytes(body: &str) -> Response<(Vec<String>, bool)> {
+        let chunks: Vec<&[u8]> = body.as_bytes().chunks(1).collect();
+        split(&chunks)
+    }
+
+    fn body(chunks: &[&str]) -> BodyStream {
+        let chunks: Vec<_> = chunks
+            .iter()
+            .map(|chunk| Ok(chunk.as_bytes().to_vec()))
+            .collect();
+        Box::pin(stream::iter(chunks))
+    }
+
+    #[test]
+    fn splits_inside_strings_and_escapes() {
+        let body = r#"[{"Name":"a \"quoted\" \\ name"}, "é\\"]"#;
+
+        let (elements, complete) = split_bytes(body).unwrap();
+
+        assert!(complete);
+        assert_eq!(
+            elements,
+            vec![r#"{"Name":"a \"quoted\" \\ name"}"#, r#" "é\\""#]
+        );
+    }
+
+    #[test]
+    fn splits_between_chunks() {
+        let (elements, complete) = split(&[b"[{\"Id\":1", b"},{\"Id\"", b":2}]"]).unwrap();
+
+        assert!(complete);
+        assert_eq!(elements, vec![r#"{"Id":1}"#, r#"{"Id":2}"#]);
+    }
+
+    #[test]
+    fn ignores_brackets_and_commas_inside_strings() {
+        let (elements, complete) = split_bytes(r#"["a]b", {"c": "d}e"}, "f,g"]"#).unwrap();
+
+        assert!(complete);
+        assert_eq!(elements, vec![r#""a]b""#, r#" {"c": "d}e"}"#, r#" "f,g""#]);
+    }
+
+    #[test]
+    fn empty_arrays() {
+        assert_eq!(split_bytes("[]").unwrap(), (vec![], true));
+        assert_eq!(split_bytes(" [ \n ] ").unwrap(), (vec![], true));
+    }
+
+    #[test]
+    fn rejects_empty_elements() {
+        assert!(split_bytes("[1,]").is_err());
+        assert!(split_bytes("[,1]").is_err());
+        assert!(split_bytes("[1,,2]").is_err());
+    }
+
+    #[test]
+    fn rejects_bodies_that_are_not_arrays() {
+        assert!(split_bytes(r#"{"Id":1}"#).is_err());
+        assert!(split_bytes("null").is_err());
+    }
+
+    #[test]
+    fn rejects_trailing_data() {
+        assert!(split_bytes("[1] \n").is_ok());
+        assert!(split_bytes("[1] [2]").is_err());
+    }
+
+    #[tokio::test]
+    async fn elements_deserializes_each_element() {
+        let items: Vec<Response<u64>> = elements(body(&["[1, 2", "3,", " 4]"])).collect().await;
+        let items: Vec<u64> = items.into_iter().map(Result::unwrap).collect();
+
+        assert_eq!(items, vec![1, 23, 4]);
+    }
+
+    #[tokio::test]
+    async fn elements_fails_on_truncated_bodies() {
+        let items: Vec<Response<u64>> = elements(body(&["[1, 2", "3, 4"])).collect().await;
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(*items[0].as_ref().unwrap(), 1);
+        assert_eq!(*items[1].as_ref().unwrap(), 23);
+        assert!(items[2].is_err());
+    }
+
+    #[tokio::test]
+    async fn elements_stops_after_an_error() {
+        let items: Vec<Response<u64>> = elements(body(&["[1]", " x", "[2]"])).collect().await;
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(*items[0].as_ref().unwrap(), 1);
+        assert!(items[1].is_err());
+    }
+
+    /// Answers the requirement pages from a project of five requirements
+    fn requirement_pages(request: &HttpRequest) -> Reply {
+        let url = reqwest::Url::parse(&request.url).unwrap();
+        let parameter = |name: &str| -> u64 {
+            url.query_pairs()
+                .find(|(parameter, _)| parameter == name)
+                .map(|(_, value)| value.parse().unwrap())
+                .unwrap()
+        };
+        let first = parameter("starting_row");
+        let last = (first + parameter("number_of_rows")).min(6);
+        let page: Vec<RequirementDto> = (first..last)
+            .map(|requirement_id| RequirementDto {
+                requirement_id: Some(requirement_id),
+                ..Default::default()
+            })
+            .collect();
+
+        Reply::json(&page)
+    }
+
+    #[tokio::test]
+    async fn paged_stops_on_a_short_page() {
+        let transport = RecordingTransport::new(requirement_pages);
+        let client = transport.client();
+
+        let requirements: Vec<_> = client
+            .requirement
+            .list_stream(1, 2)
+            .map(|requirement| requirement.unwrap().requirement_id.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(requirements, vec![1, 2, 3, 4, 5]);
+        let urls: Vec<_> = transport
+            .requests()
+            .into_iter()
+            .map(|request| request.url)
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "http://spira/projects/1/requirements?starting_row=1&number_of_rows=2",
+                "http://spira/projects/1/requirements?starting_row=3&number_of_rows=2",
+                "http://spira/projects/1/requirements?starting_row=5&number_of_rows=2",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn paged_stops_after_a_failed_page() {
+        let transport = RecordingTransport::new(|_| Reply::status(StatusCode::NOT_FOUND));
+        let client = transport.client();
+
+        let requirements: Vec<_> = client.requirement.list_stream(1, 2).collect().await;
+
+        assert_eq!(requirements.len(), 1);
+        assert!(requirements[0].is_err());
+        assert_eq!(transport.requests().len(), 1);
+    }
+}
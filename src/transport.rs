@@ -0,0 +1,503 @@
+//! Transport
+//! The resource clients send their requests through a `Transport`, reqwest being the
+//! default one. Implementing the trait allows to swap the HTTP stack, or to answer the
+//! requests in memory when testing the code using the client.
+//!
+//! ## Usage Example
+//! ```rust,no_run
+//! use futures::future::{self, BoxFuture};
+//! use spira::{
+//!     transport::{HttpRequest, HttpResponse, StatusCode, Transport, TransportError},
+//!     SpiraClient,
+//! };
+//! use std::sync::Arc;
+//!
+//! /// Answers every request with an empty list
+//! struct EmptyList;
+//!
+//! impl Transport for EmptyList {
+//!     fn send(&self, _request: HttpRequest) -> BoxFuture<'static, Result<HttpResponse, TransportError>> {
+//!         Box::pin(future::ready(Ok(HttpResponse::from_bytes(StatusCode::OK, b"[]".to_vec()))))
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let spira_client = SpiraClient::with_transport("http://spira", "api-key", "user", Arc::new(EmptyList))?;
+//!     assert!(spira_client.project.list().await?.is_empty());
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::{error::Error, fmt, pin::Pin, sync::Arc};
+
+use futures::{
+    future::BoxFuture,
+    stream::{self, Stream, StreamExt},
+};
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+
+use crate::{error::HttpStatusError, Response};
+
+pub use reqwest::StatusCode;
+
+/// The header holding the api key, redacted from the Debug output of the requests
+const API_KEY_HEADER: &str = "api-key";
+
+/// The query parameters holding credentials, redacted from the Debug output of the requests
+const SECRET_PARAMETERS: [&str; 3] = ["password", "password_question", "password_answer"];
+
+/// The value shown in place of the credentials
+const REDACTED: &str = "<redacted>";
+
+/// The errors returned by the transports
+pub type TransportError = Box<dyn Error + Send + Sync>;
+
+/// The body of a response, received in chunks
+pub type BodyStream = Pin<Box<dyn Stream<Item = Result<Vec<u8>, TransportError>> + Send>>;
+
+/// Sends the requests of the resource clients
+pub trait Transport: Send + Sync {
+    fn send(
+        &self,
+        request: HttpRequest,
+    ) -> BoxFuture<'static, Result<HttpResponse, TransportError>>;
+}
+
+/// The HTTP methods used by the Spira api
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+/// A request to the Spira api. Its Debug output redacts the api key and the passwords
+#[derive(Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    /// The full url, including the query string
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    /// The JSON body of the POST and PUT requests
+    pub body: Option<String>,
+}
+
+impl fmt::Debug for HttpRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let headers: Vec<(&str, &str)> = self
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                if name.eq_ignore_ascii_case(API_KEY_HEADER) {
+                    (name.as_str(), REDACTED)
+                } else {
+                    (name.as_str(), value.as_str())
+                }
+            })
+            .collect();
+
+        f.debug_struct("HttpRequest")
+            .field("method", &self.method)
+            .field("url", &redacted_url(&self.url))
+            .field("headers", &headers)
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+/// The url with the values of its secret query parameters replaced
+fn redacted_url(url: &str) -> String {
+    let (path, query) = match url.split_once('?') {
+        Some(parts) => parts,
+        None => return url.to_string(),
+    };
+    let query: Vec<String> = query
+        .split('&')
+        .map(|parameter| match parameter.split_once('=') {
+            Some((name, _)) if SECRET_PARAMETERS.contains(&name) => {
+                format!("{}={}", name, REDACTED)
+            }
+            _ => parameter.to_string(),
+        })
+        .collect();
+
+    format!("{}?{}", path, query.join("&"))
+}
+
+/// A response of the Spira api
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub body: BodyStream,
+}
+
+impl HttpResponse {
+    /// Builds a response with the whole body at once, e.g. for an in-memory transport
+    pub fn from_bytes(status: StatusCode, body: Vec<u8>) -> Self {
+        HttpResponse {
+            status,
+            body: Box::pin(stream::once(async move { Ok(body) })),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Fails with a HttpStatusError for the client and server error statuses
+    pub fn error_for_status(self) -> Response<Self> {
+        if self.status.is_client_error() || self.status.is_server_error() {
+            return Err(Box::new(HttpStatusError {
+                status: self.status,
+            }));
+        }
+
+        Ok(self)
+    }
+
+    /// Receives the whole body
+    pub async fn bytes(mut self) -> Response<Vec<u8>> {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = self.body.next().await {
+            bytes.extend(chunk.map_err(|error| error as Box<dyn Error>)?);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Receives the whole body and deserializes it from JSON
+    pub async fn json<T: DeserializeOwned>(self) -> Response<T> {
+        let bytes = self.bytes().await?;
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    pub fn bytes_stream(self) -> BodyStream {
+        self.body
+    }
+}
+
+/// The client the resource clients build their requests with, adding the
+/// authentication headers to every request
+#[derive(Clone)]
+pub struct HttpClient {
+    transport: Arc<dyn Transport>,
+    headers: Arc<Vec<(String, String)>>,
+}
+
+impl HttpClient {
+    pub fn new(transport: Arc<dyn Transport>, headers: Vec<(String, String)>) -> Self {
+        HttpClient {
+            transport,
+            headers: Arc::new(headers),
+        }
+    }
+
+    pub fn get(&self, url: String) -> RequestBuilder {
+        self.request(Method::Get, url)
+    }
+
+    pub fn post(&self, url: String) -> RequestBuilder {
+        self.request(Method::Post, url)
+    }
+
+    pub fn put(&self, url: String) -> RequestBuilder {
+        self.request(Method::Put, url)
+    }
+
+    pub fn delete(&self, url: String) -> RequestBuilder {
+        self.request(Method::Delete, url)
+    }
+
+    fn request(&self, method: Method, url: String) -> RequestBuilder {
+        RequestBuilder {
+            transport: self.transport.clone(),
+            request: HttpRequest {
+                method,
+                url,
+                headers: self.headers.as_ref().clone(),
+                body: None,
+            },
+            error: None,
+        }
+    }
+}
+
+/// A request being built
+pub struct RequestBuilder {
+    transport: Arc<dyn Transport>,
+    request: HttpRequest,
+    /// The first error building the request, returned by send
+    error: Option<TransportError>,
+}
+
+impl RequestBuilder {
+    pub fn body(mut self, body: String) -> Self {
+        self.request.body = Some(body);
+        self
+    }
+
+    /// Appends the url encoded parameters to the query string. When the url can't be
+    /// parsed, send fails with the parse error instead of sending the request
+    pub fn query(mut self, parameters: &[(&str, &str)]) -> Self {
+        match Url::parse_with_params(&self.request.url, parameters) {
+            Ok(url) => self.request.url = url.into(),
+            Err(error) => {
+                self.error.get_or_insert_with(|| Box::new(error));
+            }
+        }
+        self
+    }
+
+    pub async fn send(self) -> Response<HttpResponse> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let response = self
+            .transport
+            .send(self.request)
+            .await
+            .map_err(|error| error as Box<dyn Error>)?;
+
+        Ok(response)
+    }
+}
+
+/// The default transport, sending the requests with reqwest
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        ReqwestTransport { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(
+        &self,
+        request: HttpRequest,
+    ) -> BoxFuture<'static, Result<HttpResponse, TransportError>> {
+        let method = match request.method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Put => reqwest::Method::PUT,
+            Method::Delete => reqwest::Method::DELETE,
+        };
+
+        let mut builder = self.client.request(method, &request.url);
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        Box::pin(async move {
+            let response = builder.send().await?;
+            let status = response.status();
+            let body = response
+                .bytes_stream()
+                .map(|chunk| chunk.map(|bytes| bytes.to_vec()).map_err(Into::into));
+
+            Ok(HttpResponse {
+                status,
+                body: Box::pin(body),
+            })
+        })
+    }
+}
+
+/// An in-memory transport for the tests, recording the requests it receives
+#[cfg(test)]
+pub(crate) mod recording {
+    use std::{
+        sync::{Arc, Mutex},
+        task::Poll,
+    };
+
+    use futures::future::{self, BoxFuture};
+    use serde::Serialize;
+
+    use super::{HttpRequest, HttpResponse, Method, StatusCode, Transport, TransportError};
+    use crate::SpiraClient;
+
+    /// The base url of the clients built on the recording transport
+    pub(crate) const BASE_URL: &str = "http://spira";
+
+    /// The response to a recorded request
+    pub(crate) struct Reply {
+        status: StatusCode,
+        body: String,
+        /// The number of times the response yields before completing, so that
+        /// concurrent requests complete out of order
+        yields: usize,
+    }
+
+    impl Reply {
+        pub(crate) fn json<T: Serialize>(body: &T) -> Self {
+            Reply {
+                status: StatusCode::OK,
+                body: serde_json::to_string(body).unwrap(),
+                yields: 0,
+            }
+        }
+
+        pub(crate) fn status(status: StatusCode) -> Self {
+            Reply {
+                status,
+                body: String::new(),
+                yields: 0,
+            }
+        }
+
+        pub(crate) fn after_yields(mut self, yields: usize) -> Self {
+            self.yields = yields;
+            self
+        }
+    }
+
+    type Responder = Box<dyn Fn(&HttpRequest) -> Reply + Send + Sync>;
+
+    /// Answers every request with the responder and records it
+    pub(crate) struct RecordingTransport {
+        responder: Responder,
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    impl RecordingTransport {
+        pub(crate) fn new<F>(responder: F) -> Arc<Self>
+        where
+            F: Fn(&HttpRequest) -> Reply + Send + Sync + 'static,
+        {
+            Arc::new(RecordingTransport {
+                responder: Box::new(responder),
+                requests: Mutex::new(Vec::new()),
+            })
+        }
+
+        /// A client sending its requests through the transport
+        pub(crate) fn client(self: &Arc<Self>) -> SpiraClient<'static> {
+            SpiraClient::with_transport(BASE_URL, "api-key", "user", self.clone()).unwrap()
+        }
+
+        pub(crate) fn requests(&self) -> Vec<HttpRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+
+        /// The requests sent with the method, e.g. the PUT requests of the updates
+        pub(crate) fn requests_with(&self, method: Method) -> Vec<HttpRequest> {
+            self.requests()
+                .into_iter()
+                .filter(|request| request.method == method)
+                .collect()
+        }
+    }
+
+    impl Transport for RecordingTransport {
+        fn send(
+            &self,
+            request: HttpRequest,
+        ) -> BoxFuture<'static, Result<HttpResponse, TransportError>> {
+            let Reply {
+                status,
+                body,
+                mut yields,
+            } = (self.responder)(&request);
+            self.requests.lock().unwrap().push(request);
+
+            Box::pin(future::poll_fn(move |context| {
+                if yields == 0 {
+                    return Poll::Ready(Ok(HttpResponse::from_bytes(
+                        status,
+                        body.clone().into_bytes(),
+                    )));
+                }
+
+                yields -= 1;
+                context.waker().wake_by_ref();
+                Poll::Pending
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_redacts_the_credentials() {
+        let request = HttpRequest {
+            method: Method::Post,
+            url: "http://spira/users?password=p%40ss&password_question=q&password_answer=a&x=1"
+                .to_string(),
+            headers: vec![
+                ("API-Key".to_string(), "secret-key".to_string()),
+                ("username".to_string(), "fred".to_string()),
+            ],
+            body: None,
+        };
+
+        let debug = format!("{:?}", request);
+
+        for secret in ["secret-key", "p%40ss", "p@ss", "=q", "=a&"] {
+            assert!(!debug.contains(secret), "{} leaked in {}", secret, debug);
+        }
+        assert!(debug.contains("fred"));
+        assert!(debug.contains("password=<redacted>&password_question=<redacted>"));
+        assert!(debug.contains("x=1"));
+    }
+
+    #[tokio::test]
+    async fn sends_the_requests_through_the_transport() {
+        let transport = recording::RecordingTransport::new(|_| {
+            recording::Reply::json(&serde_json::json!({ "ProjectId": 1, "Name": "Library" }))
+        });
+
+        let project = transport.client().project.get(1).await.unwrap();
+
+        assert_eq!(project.name.as_deref(), Some("Library"));
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::Get);
+        assert_eq!(requests[0].url, "http://spira/projects/1");
+        assert!(requests[0]
+            .headers
+            .contains(&("api-key".to_string(), "api-key".to_string())));
+    }
+
+    #[tokio::test]
+    async fn fails_on_error_statuses() {
+        let transport =
+            recording::RecordingTransport::new(|_| recording::Reply::status(StatusCode::NOT_FOUND));
+
+        let error = transport.client().project.delete(1).await.unwrap_err();
+
+        assert_eq!(
+            error.downcast::<HttpStatusError>().unwrap().status,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[tokio::test]
+    async fn query_fails_on_invalid_urls() {
+        let transport =
+            recording::RecordingTransport::new(|_| recording::Reply::status(StatusCode::OK));
+        let client = HttpClient::new(transport.clone(), Vec::new());
+
+        let result = client
+            .post("not a url/users".to_string())
+            .query(&[("password", "secret")])
+            .send()
+            .await;
+
+        assert!(result.is_err());
+        assert!(transport.requests().is_empty());
+    }
+}